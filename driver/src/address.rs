@@ -0,0 +1,133 @@
+//! # Bus addresses
+use embedded_hal::i2c::{I2c, SevenBitAddress};
+
+use crate::{CST816S, NoIntPin, NoResetPin, ValueOutOfRange, error::is_nack};
+
+/// A 7-bit I2C address, for [`CST816S::new`].
+///
+/// Created with `try_new` or `TryFrom<u8>`, which reject values above 0x7F, so the driver never
+/// sees an address the bus cannot send. Use [`DEFAULT_ADDRESS`] for most modules, or
+/// [`CST816S::scan`] to find the address of a module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Address(SevenBitAddress);
+
+impl Address {
+    /// Returns `None` if `address` does not fit into 7 bits.
+    pub const fn try_new(address: u8) -> Option<Self> {
+        if address <= 0x7F {
+            Some(Self(address))
+        } else {
+            None
+        }
+    }
+
+    /// The raw bus address.
+    pub const fn get(self) -> SevenBitAddress {
+        self.0
+    }
+}
+
+impl From<Address> for u8 {
+    fn from(value: Address) -> Self {
+        value.0
+    }
+}
+
+impl TryFrom<u8> for Address {
+    type Error = ValueOutOfRange;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::try_new(value).ok_or(ValueOutOfRange(value))
+    }
+}
+
+/// Address of the CST816S and most modules built around it.
+pub const DEFAULT_ADDRESS: Address = Address(0x15);
+
+/// Addresses CST816 modules have been seen at, for [`CST816S::scan`].
+///
+/// Some clones answer at 0x2A instead of [`DEFAULT_ADDRESS`].
+pub const KNOWN_ADDRESSES: [Address; 2] = [DEFAULT_ADDRESS, Address(0x2A)];
+
+impl<I2C> CST816S<I2C, NoIntPin, NoResetPin>
+where
+    I2C: I2c,
+{
+    /// Find the address a module answers at, before creating the driver.
+    ///
+    /// Reads the `ChipId` register at each of `candidates`, usually [`KNOWN_ADDRESSES`], and
+    /// returns the first address that acknowledges, or `Ok(None)`. Other bus errors than a NACK
+    /// are returned. The chip does not answer while asleep, so reset it first.
+    ///
+    /// Called as `CST816S::scan(&mut i2c, &KNOWN_ADDRESSES)`, the pin types of this impl are
+    /// only there to make the call unambiguous.
+    pub fn scan(i2c: &mut I2C, candidates: &[Address]) -> Result<Option<Address>, I2C::Error> {
+        let mut chip_id = [0];
+        for &address in candidates {
+            match i2c.write_read(address.get(), &[0xA7], &mut chip_id) {
+                Ok(()) => return Ok(Some(address)),
+                Err(e) if is_nack(&e) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+    use embedded_hal_mock::eh1::i2c;
+
+    fn chip_id(address: SevenBitAddress) -> i2c::Transaction {
+        i2c::Transaction::write_read(address, vec![0xA7], vec![0xB4])
+    }
+
+    fn nack(address: SevenBitAddress) -> i2c::Transaction {
+        chip_id(address).with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address))
+    }
+
+    #[test]
+    fn scan_finds_clone_address() {
+        let mut i2c_device = i2c::Mock::new(&[nack(0x15), chip_id(0x2A)]);
+
+        assert_eq!(
+            CST816S::scan(&mut i2c_device, &KNOWN_ADDRESSES),
+            Ok(Address::try_new(0x2A))
+        );
+
+        i2c_device.done();
+    }
+
+    #[test]
+    fn scan_without_answer() {
+        let mut i2c_device = i2c::Mock::new(&[nack(0x15), nack(0x2A)]);
+
+        assert_eq!(CST816S::scan(&mut i2c_device, &KNOWN_ADDRESSES), Ok(None));
+
+        i2c_device.done();
+    }
+
+    #[test]
+    fn scan_returns_bus_errors() {
+        let mut i2c_device = i2c::Mock::new(&[chip_id(0x15).with_error(ErrorKind::Bus)]);
+
+        assert_eq!(
+            CST816S::scan(&mut i2c_device, &KNOWN_ADDRESSES),
+            Err(ErrorKind::Bus)
+        );
+
+        i2c_device.done();
+    }
+
+    #[test]
+    fn address_range() {
+        assert_eq!(Address::try_new(0x15), Some(DEFAULT_ADDRESS));
+        assert_eq!(Address::try_new(0x7F).map(u8::from), Some(0x7F));
+        assert_eq!(Address::try_new(0x80), None);
+        assert_eq!(Address::try_from(0xFF), Err(ValueOutOfRange(0xFF)));
+        assert_eq!(KNOWN_ADDRESSES.map(Address::get), [0x15, 0x2A]);
+    }
+}
@@ -0,0 +1,2291 @@
+//! # Typed configuration
+use embedded_hal::{digital::OutputPin, i2c::I2c};
+
+use device_driver::RegisterInterface;
+
+use crate::{
+    Address, CST816S, Error, LpScanCurrent, LpScanFrequency, LpScanThreshold, LpScanWindow,
+    PulseWidth, ScanPeriod, SleepSeconds, WakeMinutes,
+    device::{DeviceError, RegisterAddress, field_sets},
+};
+
+/// Number of bytes in the configuration block 0xEC..=0xFE, see [`CST816S::dump_config`].
+pub const CONFIG_BLOCK_LEN: usize = 19;
+
+/// Address of the first register of the configuration block, `MotionMask`.
+const CONFIG_BLOCK_START: RegisterAddress = 0xEC;
+
+/// `tan(deg) * 10` rounded to the nearest integer for 0..=87°, the `MotionSlAngle` encoding.
+///
+/// 88° and up no longer fit the register.
+const SLIDE_ANGLE_TAN_X10: [u8; 88] = [
+    0, 0, 0, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2, 3, 3, 3, 3, 3, 4, 4, 4, 4, 4, 5, 5, 5, 5, 6, 6, 6,
+    6, 6, 7, 7, 7, 8, 8, 8, 8, 9, 9, 9, 10, 10, 10, 11, 11, 12, 12, 12, 13, 13, 14, 14, 15, 15, 16,
+    17, 17, 18, 19, 20, 21, 21, 22, 24, 25, 26, 27, 29, 31, 33, 35, 37, 40, 43, 47, 51, 57, 63, 71,
+    81, 95, 114, 143, 191,
+];
+
+/// Decoded contents of the `IrqCtl` register (0xFA), controlling when the interrupt pin pulses low.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IrqConfig {
+    /// Only generate one low pulse when a long press is detected
+    pub once_wlp: bool,
+    /// Generate low pulses when a gesture is detected
+    pub en_motion: bool,
+    /// Generate low pulses when the touch changes
+    pub en_change: bool,
+    /// Generate low pulses when a touch is detected
+    pub en_touch: bool,
+    /// Periodically generate low pulses to test the interrupt pin
+    pub en_test: bool,
+}
+
+impl From<field_sets::IrqCtl> for IrqConfig {
+    fn from(value: field_sets::IrqCtl) -> Self {
+        Self {
+            once_wlp: value.once_wlp(),
+            en_motion: value.en_motion(),
+            en_change: value.en_change(),
+            en_touch: value.en_touch(),
+            en_test: value.en_test(),
+        }
+    }
+}
+
+/// The reset value of the `IrqCtl` register, with every interrupt source off.
+///
+/// [`Config::default`] instead enables the sources [`CST816S::init_config`] writes.
+impl Default for IrqConfig {
+    fn default() -> Self {
+        field_sets::IrqCtl::new().into()
+    }
+}
+
+impl IrqConfig {
+    /// Pulse on every touch and every change of the touch, `0x60`.
+    ///
+    /// For following the finger with [`CST816S::poll`] or [`CST816S::touch_state`]. The chip
+    /// pulses for every new coordinate, so the motion mask does not matter here.
+    pub const fn touch_tracking() -> Self {
+        Self {
+            once_wlp: false,
+            en_motion: false,
+            en_change: true,
+            en_touch: true,
+            en_test: false,
+        }
+    }
+
+    /// Pulse only when a gesture is detected, `0x10`.
+    ///
+    /// For low power gesture UIs that sleep between swipes and taps. Pair it with continuous
+    /// slides off in the [`MotionMaskConfig`], otherwise a long slide pulses repeatedly, and
+    /// with double click off unless the UI needs it, as waiting for a second tap delays single
+    /// clicks.
+    pub const fn gestures_only() -> Self {
+        Self {
+            once_wlp: false,
+            en_motion: true,
+            en_change: false,
+            en_touch: false,
+            en_test: false,
+        }
+    }
+
+    /// Pulse on gestures, but only once for a long press, `0x11`.
+    ///
+    /// Without `once_wlp` the chip keeps pulsing while the finger is held. Pairs with the same
+    /// [`MotionMaskConfig`] as [`IrqConfig::gestures_only`].
+    pub const fn long_press_once() -> Self {
+        Self {
+            once_wlp: true,
+            ..Self::gestures_only()
+        }
+    }
+
+    pub(crate) fn write_to(&self, irq_ctl: &mut field_sets::IrqCtl) {
+        irq_ctl.set_once_wlp(self.once_wlp);
+        irq_ctl.set_en_motion(self.en_motion);
+        irq_ctl.set_en_change(self.en_change);
+        irq_ctl.set_en_touch(self.en_touch);
+        irq_ctl.set_en_test(self.en_test);
+    }
+}
+
+/// Set of interrupt sources, with the bit positions of the `IrqCtl` register (0xFA).
+///
+/// A dependency free take on the `bitflags` crate. Sources combine with `|`, and convert to and
+/// from [`IrqConfig`]:
+///
+/// ```
+/// use cst816s_device_driver::{IrqConfig, IrqSources};
+///
+/// let sources = IrqSources::TOUCH | IrqSources::CHANGE;
+/// assert_eq!(sources.bits(), 0x60);
+/// assert_eq!(IrqConfig::from(sources), IrqConfig::touch_tracking());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct IrqSources(u8);
+
+impl IrqSources {
+    /// Only pulse once for a long press, `OnceWLP` (bit 0)
+    pub const ONCE_WLP: Self = Self(1 << 0);
+    /// Pulse when a gesture is detected, `EnMotion` (bit 4)
+    pub const MOTION: Self = Self(1 << 4);
+    /// Pulse when the touch changes, `EnChange` (bit 5)
+    pub const CHANGE: Self = Self(1 << 5);
+    /// Pulse when a touch is detected, `EnTouch` (bit 6)
+    pub const TOUCH: Self = Self(1 << 6);
+    /// Pulse periodically to test the interrupt pin, `EnTest` (bit 7)
+    pub const TEST: Self = Self(1 << 7);
+
+    /// No sources.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Every source.
+    pub const fn all() -> Self {
+        Self(Self::ONCE_WLP.0 | Self::MOTION.0 | Self::CHANGE.0 | Self::TOUCH.0 | Self::TEST.0)
+    }
+
+    /// The `IrqCtl` register value.
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// The sources in the register value `bits`, ignoring the reserved bits 1 to 3.
+    pub const fn from_bits_truncate(bits: u8) -> Self {
+        Self(bits & Self::all().0)
+    }
+
+    /// Whether every source in `other` is also in `self`.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Whether no source is set.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl core::ops::BitOr for IrqSources {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl core::ops::BitOrAssign for IrqSources {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl core::ops::BitAnd for IrqSources {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl core::ops::Sub for IrqSources {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 & !rhs.0)
+    }
+}
+
+impl From<IrqSources> for IrqConfig {
+    fn from(sources: IrqSources) -> Self {
+        Self {
+            once_wlp: sources.contains(IrqSources::ONCE_WLP),
+            en_motion: sources.contains(IrqSources::MOTION),
+            en_change: sources.contains(IrqSources::CHANGE),
+            en_touch: sources.contains(IrqSources::TOUCH),
+            en_test: sources.contains(IrqSources::TEST),
+        }
+    }
+}
+
+impl From<IrqConfig> for IrqSources {
+    fn from(config: IrqConfig) -> Self {
+        let mut sources = Self::empty();
+        for (enabled, source) in [
+            (config.once_wlp, Self::ONCE_WLP),
+            (config.en_motion, Self::MOTION),
+            (config.en_change, Self::CHANGE),
+            (config.en_touch, Self::TOUCH),
+            (config.en_test, Self::TEST),
+        ] {
+            if enabled {
+                sources |= source;
+            }
+        }
+        sources
+    }
+}
+
+/// Decoded contents of the `MotionMask` register (0xEC), selecting the motions the chip reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MotionMaskConfig {
+    /// Report double clicks
+    pub double_click: bool,
+    /// Report continuous up/down slides
+    pub continuous_ud: bool,
+    /// Report continuous left/right slides
+    pub continuous_lr: bool,
+}
+
+impl From<field_sets::MotionMask> for MotionMaskConfig {
+    fn from(value: field_sets::MotionMask) -> Self {
+        Self {
+            double_click: value.en_d_click(),
+            continuous_ud: value.en_con_ud(),
+            continuous_lr: value.en_con_lr(),
+        }
+    }
+}
+
+impl MotionMaskConfig {
+    pub(crate) fn write_to(&self, mask: &mut field_sets::MotionMask) {
+        mask.set_en_d_click(self.double_click);
+        mask.set_en_con_ud(self.continuous_ud);
+        mask.set_en_con_lr(self.continuous_lr);
+    }
+}
+
+/// Wake-from-sleep tuning written to the `LpScanTH`, `LpScanWin`, `LpScanFreq` and `LpScanIdac`
+/// registers (0xF5..=0xF8).
+///
+/// Lower `threshold`, `frequency` and `idac` values and a larger `window` make the chip wake up
+/// more easily, at the cost of a higher current draw while it is asleep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LowPowerConfig {
+    /// Wake-up threshold
+    pub threshold: LpScanThreshold,
+    /// Scanning range
+    pub window: LpScanWindow,
+    /// Scanning frequency
+    pub frequency: LpScanFrequency,
+    /// Scanning current
+    pub idac: LpScanCurrent,
+}
+
+/// Level of the I2C and interrupt pins, the `En1v8` bit of `IOCtl`.
+///
+/// Set with [`CST816S::set_io_voltage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum IoVoltage {
+    /// Drive the pins at VDD level, the reset value
+    #[default]
+    Vdd,
+    /// Drive the pins at 1.8V
+    V1_8,
+}
+
+/// Maximum number of registers in a [`ConfigDiff`], one for each register a [`Config`] covers.
+pub const CONFIG_DIFF_LEN: usize = 9;
+
+/// A register that did not hold the expected value, see [`CST816S::verify_config`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct RegisterMismatch {
+    /// Register address
+    pub register: u8,
+    /// Value `Config` asked for
+    pub expected: u8,
+    /// Value read from the chip
+    pub actual: u8,
+}
+
+/// The registers that differ from a [`Config`], from [`CST816S::verify_config`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigDiff {
+    mismatches: heapless::Vec<RegisterMismatch, CONFIG_DIFF_LEN>,
+}
+
+impl ConfigDiff {
+    /// Whether every register holds the expected value.
+    pub fn is_empty(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    /// The differing registers, in address order.
+    pub fn mismatches(&self) -> &[RegisterMismatch] {
+        &self.mismatches
+    }
+
+    /// The mismatch of `register`, if it differs.
+    pub fn get(&self, register: u8) -> Option<RegisterMismatch> {
+        self.mismatches
+            .iter()
+            .find(|mismatch| mismatch.register == register)
+            .copied()
+    }
+}
+
+/// Settings written by [`CST816S::apply`], covering the registers a typical setup touches.
+///
+/// The [`Default`] is the setup of [`CST816S::init_config`]. Fields set to `None` are not
+/// written and keep the chip's value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Config {
+    /// When the interrupt pin pulses, default: on touch, change, motion and once on long press
+    pub irq: IrqConfig,
+    /// Motions the chip reports, default: all on
+    pub motion_mask: MotionMaskConfig,
+    /// Interrupt pulse width, default: 0.1ms
+    pub irq_pulse_width: PulseWidth,
+    /// Normal scan period, see [`CST816S::set_normal_scan_period`]. Default: 10ms
+    pub scan_period: ScanPeriod,
+    /// Let the chip enter low power mode on its own, default: off
+    pub auto_sleep: bool,
+    /// Time without touch before auto sleep, see [`CST816S::set_auto_sleep_time`]
+    pub auto_sleep_time: Option<SleepSeconds>,
+    /// Time between recalibrations in low power mode, see [`CST816S::set_lp_auto_wake_time`]
+    pub lp_auto_wake_time: Option<WakeMinutes>,
+    /// Seconds of long press before the chip resets, `LongPressTime`. 0 disables, chip default: 10
+    pub long_press_time_s: Option<u8>,
+    /// Seconds of touch without a gesture before the chip resets, `AutoReset`. 0 disables, chip
+    /// default: 0
+    pub auto_reset_s: Option<u8>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            irq: IrqConfig {
+                once_wlp: true,
+                en_motion: true,
+                en_change: true,
+                en_touch: true,
+                en_test: false,
+            },
+            motion_mask: MotionMaskConfig {
+                double_click: true,
+                continuous_ud: true,
+                continuous_lr: true,
+            },
+            irq_pulse_width: PulseWidth::new(1),
+            scan_period: ScanPeriod::default(),
+            auto_sleep: false,
+            auto_sleep_time: None,
+            lp_auto_wake_time: None,
+            long_press_time_s: None,
+            auto_reset_s: None,
+        }
+    }
+}
+
+impl Config {
+    /// Longest battery life: the chip sleeps on its own and only pulses for gestures.
+    ///
+    /// - `IrqCtl` 0x10: pulse on gestures only ([`IrqConfig::gestures_only`])
+    /// - `MotionMask` 0x01: double click on, continuous scrolling off
+    /// - `IrqPulseWidth` 10: 1ms
+    /// - `NorScanPer` 10: scan every 100ms
+    /// - `DisAutoSleep` 0: auto sleep on, `AutoSleepTime` 2: after 2s without touch
+    /// - `LpAutoWakeTime` 5: recalibrate every 5 minutes while asleep
+    ///
+    /// A touch wakes the chip through the low power scan, so its registers must stay sensitive;
+    /// [`CST816S::apply_low_power_profile`] also writes the reset values of
+    /// [`LowPowerConfig`].
+    pub fn low_power() -> Self {
+        Self {
+            irq: IrqConfig::gestures_only(),
+            motion_mask: MotionMaskConfig {
+                double_click: true,
+                continuous_ud: false,
+                continuous_lr: false,
+            },
+            irq_pulse_width: PulseWidth::default(),
+            scan_period: ScanPeriod::from_ms(100).unwrap(),
+            auto_sleep: true,
+            auto_sleep_time: Some(SleepSeconds::default()),
+            lp_auto_wake_time: Some(WakeMinutes::default()),
+            long_press_time_s: None,
+            auto_reset_s: None,
+        }
+    }
+
+    /// Lowest latency: the chip never sleeps and pulses for every change of the touch.
+    ///
+    /// - `IrqCtl` 0x60: pulse on touch and change ([`IrqConfig::touch_tracking`])
+    /// - `MotionMask` 0x07: all motions on
+    /// - `IrqPulseWidth` 1: 0.1ms
+    /// - `NorScanPer` 1: scan every 10ms
+    /// - `DisAutoSleep` 0xFE: auto sleep off
+    pub fn responsive() -> Self {
+        Self {
+            irq: IrqConfig::touch_tracking(),
+            scan_period: ScanPeriod::from_ms(10).unwrap(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Writing the configuration without the reset pin, so reports can reapply it.
+impl<I2C, TPINT, TPRST> CST816S<I2C, TPINT, TPRST>
+where
+    I2C: I2c,
+{
+    /// Write a validated `config`, shared by [`CST816S::apply`] and [`CST816S::init_config`].
+    pub(crate) fn write_config(&mut self, config: &Config) -> Result<(), DeviceError<I2C::Error>> {
+        self.device
+            .irq_ctl()
+            .write(|irq_ctl| config.irq.write_to(irq_ctl))?;
+        if self.variant.has_motion_mask() {
+            self.device
+                .motion_mask()
+                .write(|m| config.motion_mask.write_to(m))?;
+        }
+        let dis_auto_sleep = if config.auto_sleep { 0 } else { 0xFE };
+        self.device
+            .dis_auto_sleep()
+            .write(|m| m.set_value(dis_auto_sleep))?;
+        self.device
+            .irq_pulse_width()
+            .write(|m| m.set_value(config.irq_pulse_width))?;
+        self.device
+            .nor_scan_per()
+            .write(|m| m.set_value(config.scan_period))?;
+        if let Some(time) = config.auto_sleep_time {
+            self.device.auto_sleep_time().write(|m| m.set_value(time))?;
+        }
+        if let Some(minutes) = config.lp_auto_wake_time {
+            self.device
+                .lp_auto_wake_time()
+                .write(|m| m.set_value(minutes))?;
+        }
+        if let Some(seconds) = config.long_press_time_s {
+            self.device
+                .long_press_time()
+                .write(|m| m.set_value(seconds))?;
+        }
+        if let Some(seconds) = config.auto_reset_s {
+            self.device.auto_reset().write(|m| m.set_value(seconds))?;
+        }
+        self.applied_config = Some(*config);
+        self.muted_irq = None;
+        self.reapply_pending = false;
+        Ok(())
+    }
+
+    /// Change the configuration [`CST816S::reapply_config`] writes, after a setter changed one of
+    /// its registers. Does nothing if no configuration was written yet.
+    pub(crate) fn update_applied_config(&mut self, f: impl FnOnce(&mut Config)) {
+        if let Some(applied) = &mut self.applied_config {
+            f(applied);
+        }
+    }
+
+    /// Write the last applied configuration again if [`CST816S::set_auto_reapply`] is on and the
+    /// chip was reset, called before a report is decoded.
+    pub(crate) fn auto_reapply_config(&mut self) -> Result<(), DeviceError<I2C::Error>> {
+        if !self.auto_reapply {
+            return Ok(());
+        }
+        let Some(config) = self.applied_config else {
+            return Ok(());
+        };
+        let expected = match self.muted_irq {
+            Some(_) => IrqConfig::default(),
+            None => config.irq,
+        };
+        // `IrqCtl` catches a reset unless the configured interrupts equal the chip default, in
+        // which case `DisAutoSleep` falling back to 0 gives it away.
+        let reset = self.reapply_pending
+            || IrqConfig::from(self.device.irq_ctl().read()?) != expected
+            || (!config.auto_sleep && self.device.dis_auto_sleep().read()?.value() == 0);
+        if reset {
+            let muted_irq = self.muted_irq;
+            self.write_config(&config)?;
+            if muted_irq.is_some() {
+                self.device
+                    .irq_ctl()
+                    .write(|irq_ctl| IrqConfig::default().write_to(irq_ctl))?;
+                self.muted_irq = muted_irq;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<I2C, TPINT, TPRST> CST816S<I2C, TPINT, TPRST>
+where
+    I2C: I2c,
+    TPRST: OutputPin,
+{
+    /// Create a driver and [`CST816S::apply`] `config` to the chip.
+    ///
+    /// The chip must be awake, so reset it before if it may be asleep.
+    pub fn with_config(
+        i2c: I2C,
+        address: Address,
+        interrupt_pin: TPINT,
+        reset_pin: TPRST,
+        config: &Config,
+    ) -> Result<Self, Error<I2C::Error>> {
+        let mut driver = Self::new(i2c, address, interrupt_pin, reset_pin);
+        driver.apply(config)?;
+        Ok(driver)
+    }
+
+    /// Write `config` to the chip.
+    ///
+    /// The motion mask is only written if the [`ChipVariant`](crate::ChipVariant) has it.
+    pub fn apply(&mut self, config: &Config) -> Result<(), Error<I2C::Error>> {
+        self.write_config(config)?;
+        Ok(())
+    }
+
+    /// Apply [`Config::low_power`] and the default [`LowPowerConfig`], so a touch still wakes
+    /// the chip.
+    pub fn apply_low_power_profile(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.apply(&Config::low_power())?;
+        self.set_low_power_config(&LowPowerConfig::default())
+    }
+
+    /// Apply [`Config::responsive`].
+    pub fn apply_responsive_profile(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.apply(&Config::responsive())
+    }
+
+    /// Check whether the chip lost the configuration written by [`CST816S::apply`],
+    /// [`CST816S::with_config`] or [`CST816S::init_config`].
+    ///
+    /// The chip resets itself after [`Config::auto_reset_s`] seconds of touch without a valid
+    /// gesture and after [`Config::long_press_time_s`] seconds of long press, which puts every
+    /// register back to its default. Long press heavy UIs trip this, and afterwards the
+    /// interrupt pin may stay silent. This reads only the `IrqCtl` register and compares it
+    /// with the last written [`IrqConfig`], so it is cheap enough to call after every long
+    /// press. It cannot notice a reset if the configured [`IrqConfig`] equals the chip default.
+    ///
+    /// Returns `false` if no configuration was written yet. While interrupts are turned off with
+    /// [`CST816S::set_interrupts_enabled`] the register is expected to be all zero.
+    pub fn needs_reconfigure(&mut self) -> Result<bool, DeviceError<I2C::Error>> {
+        let Some(config) = self.applied_config else {
+            return Ok(false);
+        };
+        let expected = match self.muted_irq {
+            Some(_) => IrqConfig::default(),
+            None => config.irq,
+        };
+        Ok(self.irq_config()? != expected)
+    }
+
+    /// Write the last applied configuration again, see [`CST816S::needs_reconfigure`].
+    ///
+    /// Changes made since with the single register setters, like [`CST816S::set_motion_mask`]
+    /// or [`CST816S::set_scan_period`], are part of it. Does nothing if no configuration was
+    /// written yet.
+    pub fn reapply_config(&mut self) -> Result<(), DeviceError<I2C::Error>> {
+        match self.applied_config {
+            Some(config) => self.write_config(&config),
+            None => Ok(()),
+        }
+    }
+
+    /// Write the last applied configuration again before the next report after a reset.
+    ///
+    /// The chip loses its configuration when it is reset, by [`CST816S::reset`] or by itself
+    /// (see [`CST816S::needs_reconfigure`]), and gestures stop working until it is written
+    /// again. With this on, the driver writes the configuration from [`CST816S::apply`] again
+    /// after [`CST816S::reset`], and checks the `IrqCtl` register before every report to catch
+    /// resets the chip did on its own. If `IrqCtl` holds the expected value and auto sleep is
+    /// off in the configuration, `DisAutoSleep` is read as well, as it goes back to 0 on reset.
+    /// That check costs one or two single byte reads per report. Has no effect until a
+    /// configuration was applied. Off by default.
+    ///
+    /// The setting does not survive [`CST816S::split`]: the [`TouchReader`](crate::TouchReader)
+    /// that decodes the reports does not know the configuration, so call
+    /// [`CST816S::reapply_config_if_reset`] on the [`ConfigHandle`](crate::ConfigHandle) instead.
+    /// [`TouchReader::join`](crate::TouchReader::join) turns it back on.
+    pub fn set_auto_reapply(&mut self, enabled: bool) {
+        self.auto_reapply = enabled;
+    }
+
+    /// [`CST816S::reapply_config`] if [`CST816S::needs_reconfigure`] detects a reset, returning
+    /// whether the configuration was written.
+    pub fn reapply_config_if_reset(&mut self) -> Result<bool, DeviceError<I2C::Error>> {
+        let reset = self.needs_reconfigure()?;
+        if reset {
+            self.reapply_config()?;
+        }
+        Ok(reset)
+    }
+
+    /// Write the datasheet reset value to the configuration registers.
+    ///
+    /// Gets the chip back to a known state after experimenting with the sensitivity settings,
+    /// without a power cycle. The values are the `RESET_VALUE`s of the register map;
+    /// `MotionMask` and `IrqCtl` have none and are cleared. `MotionSlAngle` and `LpScanIdac`
+    /// have no documented reset value and `IOCtl` changes the pin levels the bus relies on, so
+    /// these are left alone.
+    ///
+    /// Forgets the configuration written by [`CST816S::apply`], so
+    /// [`CST816S::reapply_config`] does nothing afterwards.
+    pub fn reset_to_defaults(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.device.motion_mask().write(|_| ())?;
+        self.device.irq_pulse_width().write(|_| ())?;
+        self.device.nor_scan_per().write(|_| ())?;
+        self.device.lp_auto_wake_time().write(|_| ())?;
+        self.device.lp_scan_th().write(|_| ())?;
+        self.device.lp_scan_win().write(|_| ())?;
+        self.device.lp_scan_freq().write(|_| ())?;
+        self.device.auto_sleep_time().write(|_| ())?;
+        self.device.irq_ctl().write(|_| ())?;
+        self.device.auto_reset().write(|_| ())?;
+        self.device.long_press_time().write(|_| ())?;
+        self.device.dis_auto_sleep().write(|_| ())?;
+        self.applied_config = None;
+        self.muted_irq = None;
+        Ok(())
+    }
+
+    /// Read the whole configuration block, registers 0xEC to 0xFE, in one transaction.
+    ///
+    /// `buf[0]` holds `MotionMask` (0xEC) and `buf[18]` holds `DisAutoSleep` (0xFE). Together with
+    /// [`CST816S::restore_config`] this saves the settings before deep sleep or for logging.
+    pub fn dump_config(
+        &mut self,
+        buf: &mut [u8; CONFIG_BLOCK_LEN],
+    ) -> Result<(), DeviceError<I2C::Error>> {
+        self.device
+            .interface
+            .read_register(CONFIG_BLOCK_START, CONFIG_BLOCK_LEN as u32 * 8, buf)
+    }
+
+    /// Write a block read with [`CST816S::dump_config`] back in one transaction.
+    ///
+    /// The block includes the read-only `LpScanRaw` registers (0xF0 to 0xF3), which are written
+    /// back with the rest; the datasheet documents no effect for writes to them.
+    pub fn restore_config(
+        &mut self,
+        buf: &[u8; CONFIG_BLOCK_LEN],
+    ) -> Result<(), DeviceError<I2C::Error>> {
+        self.device
+            .interface
+            .write_register(CONFIG_BLOCK_START, CONFIG_BLOCK_LEN as u32 * 8, buf)
+    }
+
+    /// Read the configuration back from the chip, in one transaction.
+    ///
+    /// Every field is filled in, including the ones that are `None` in the [`Config`] that was
+    /// written, so compare with a config that sets them all. Returns [`Error::OutOfRange`] if a
+    /// register holds a value the matching field can not represent.
+    pub fn read_config(&mut self) -> Result<Config, Error<I2C::Error>> {
+        let mut block = [0; CONFIG_BLOCK_LEN];
+        self.dump_config(&mut block)?;
+        let register = |address: RegisterAddress| block[usize::from(address - CONFIG_BLOCK_START)];
+
+        Ok(Config {
+            irq: field_sets::IrqCtl::from([register(0xFA)]).into(),
+            motion_mask: field_sets::MotionMask::from([register(0xEC)]).into(),
+            irq_pulse_width: register(0xED).try_into()?,
+            scan_period: register(0xEE).try_into()?,
+            auto_sleep: register(0xFE) == 0,
+            auto_sleep_time: Some(register(0xF9).try_into()?),
+            lp_auto_wake_time: Some(register(0xF4).try_into()?),
+            long_press_time_s: Some(register(0xFC)),
+            auto_reset_s: Some(register(0xFB)),
+        })
+    }
+
+    /// Write `config` like [`CST816S::apply`], but as one read and one write of the whole
+    /// configuration block.
+    ///
+    /// [`CST816S::apply`] addresses every register on its own, which on a slow shared bus
+    /// leaves time for the chip to enter auto sleep halfway through. This reads the block with
+    /// [`CST816S::dump_config`], updates the registers set by `config` and writes it back with
+    /// [`CST816S::restore_config`]. Registers `config` leaves out, like the low power scanning
+    /// ones or an unset [`Config::long_press_time_s`], keep their current value.
+    pub fn write_config_block(&mut self, config: &Config) -> Result<(), DeviceError<I2C::Error>> {
+        self.write_block(config)?;
+        Ok(())
+    }
+
+    /// Write every field of `config` with [`CST816S::write_config_block`] and read the block
+    /// back to check the chip took it.
+    ///
+    /// Meant to replay a [`Config`] captured with [`CST816S::read_config`] on other units. Every
+    /// register of the block is compared, except the read-only `LpScanRaw` values, and the
+    /// first one that reads back different is returned as [`Error::ReadBackMismatch`].
+    pub fn apply_config(&mut self, config: &Config) -> Result<(), Error<I2C::Error>> {
+        let written = self.write_block(config)?;
+        let mut read = [0; CONFIG_BLOCK_LEN];
+        self.dump_config(&mut read)?;
+        let mismatch = (CONFIG_BLOCK_START..=u8::MAX)
+            .zip(written.iter().zip(&read))
+            .find(|&(register, (written, read))| {
+                !matches!(register, 0xF0..=0xF3) && written != read
+            });
+        match mismatch {
+            Some((register, _)) => Err(Error::ReadBackMismatch { register }),
+            None => Ok(()),
+        }
+    }
+
+    /// Shared by [`CST816S::write_config_block`] and [`CST816S::apply_config`], returns the
+    /// block as written.
+    fn write_block(
+        &mut self,
+        config: &Config,
+    ) -> Result<[u8; CONFIG_BLOCK_LEN], DeviceError<I2C::Error>> {
+        let mut block = [0; CONFIG_BLOCK_LEN];
+        self.dump_config(&mut block)?;
+        self.fill_block(config, &mut block);
+        self.restore_config(&block)?;
+        self.applied_config = Some(*config);
+        self.muted_irq = None;
+        Ok(block)
+    }
+
+    /// Compare the registers covered by `expected` with the chip.
+    ///
+    /// Some firmware revisions silently ignore writes to some registers, this lists each
+    /// register that differs with the expected and the actual value. Fields set to `None` are
+    /// not checked, neither is `MotionMask` on variants without it, and only the bits of
+    /// `IrqCtl` and `MotionMask` that `expected` sets are compared. A non-zero `DisAutoSleep`
+    /// counts as disabled auto sleep. Reads the block in one transaction.
+    pub fn verify_config(&mut self, expected: &Config) -> Result<ConfigDiff, Error<I2C::Error>> {
+        let mut actual = [0; CONFIG_BLOCK_LEN];
+        self.dump_config(&mut actual)?;
+        let mut wanted = actual;
+        self.fill_block(expected, &mut wanted);
+        let dis_auto_sleep = usize::from(0xFE - CONFIG_BLOCK_START);
+        if !expected.auto_sleep && actual[dis_auto_sleep] != 0 {
+            wanted[dis_auto_sleep] = actual[dis_auto_sleep];
+        }
+
+        let mut diff = ConfigDiff::default();
+        for (register, (&expected, &actual)) in
+            (CONFIG_BLOCK_START..=u8::MAX).zip(wanted.iter().zip(&actual))
+        {
+            if expected != actual {
+                // At most one entry per register `Config` covers, so this can not overflow
+                diff.mismatches
+                    .push(RegisterMismatch {
+                        register,
+                        expected,
+                        actual,
+                    })
+                    .ok();
+            }
+        }
+        Ok(diff)
+    }
+
+    /// Set the registers covered by `config` in a configuration block.
+    fn fill_block(&self, config: &Config, block: &mut [u8; CONFIG_BLOCK_LEN]) {
+        let offset = |register: RegisterAddress| usize::from(register - CONFIG_BLOCK_START);
+
+        if self.variant.has_motion_mask() {
+            let mut mask = field_sets::MotionMask::from([block[offset(0xEC)]]);
+            config.motion_mask.write_to(&mut mask);
+            [block[offset(0xEC)]] = mask.into();
+        }
+        block[offset(0xED)] = config.irq_pulse_width.get();
+        block[offset(0xEE)] = config.scan_period.get();
+        if let Some(minutes) = config.lp_auto_wake_time {
+            block[offset(0xF4)] = minutes.get();
+        }
+        if let Some(time) = config.auto_sleep_time {
+            block[offset(0xF9)] = time.get();
+        }
+        let mut irq_ctl = field_sets::IrqCtl::from([block[offset(0xFA)]]);
+        config.irq.write_to(&mut irq_ctl);
+        [block[offset(0xFA)]] = irq_ctl.into();
+        if let Some(seconds) = config.auto_reset_s {
+            block[offset(0xFB)] = seconds;
+        }
+        if let Some(seconds) = config.long_press_time_s {
+            block[offset(0xFC)] = seconds;
+        }
+        block[offset(0xFE)] = if config.auto_sleep { 0 } else { 0xFE };
+    }
+
+    /// Read back the interrupt configuration from the `IrqCtl` register.
+    ///
+    /// Useful to check that the chip kept its settings after a glitch or brownout.
+    pub fn irq_config(&mut self) -> Result<IrqConfig, DeviceError<I2C::Error>> {
+        Ok(self.device.irq_ctl().read()?.into())
+    }
+
+    /// Write the interrupt configuration to the `IrqCtl` register.
+    ///
+    /// This writes every bit of the register. [`CST816S::update_irq_ctl`] and
+    /// [`CST816S::set_irq_test_mode`] read the register first and only change some bits.
+    ///
+    /// This turns interrupts back on if they were turned off with
+    /// [`CST816S::set_interrupts_enabled`].
+    pub fn set_irq_config(&mut self, config: IrqConfig) -> Result<(), DeviceError<I2C::Error>> {
+        self.device
+            .irq_ctl()
+            .write(|irq_ctl| config.write_to(irq_ctl))?;
+        self.update_applied_config(|applied| applied.irq = config);
+        self.muted_irq = None;
+        Ok(())
+    }
+
+    /// Turn all touch interrupts off, or back on, without losing the interrupt configuration.
+    ///
+    /// Disabling saves the last written [`IrqConfig`], or the one read from the chip if none was
+    /// written yet, and clears the `IrqCtl` register. Enabling writes the saved configuration
+    /// back. Useful to keep the interrupt line quiet during a modal animation. Calling it twice
+    /// with the same value does nothing.
+    pub fn set_interrupts_enabled(&mut self, enabled: bool) -> Result<(), DeviceError<I2C::Error>> {
+        if enabled {
+            if let Some(config) = self.muted_irq {
+                self.set_irq_config(config)?;
+            }
+            return Ok(());
+        }
+        if self.muted_irq.is_some() {
+            return Ok(());
+        }
+        let config = match self.applied_config {
+            Some(applied) => applied.irq,
+            None => self.irq_config()?,
+        };
+        self.device
+            .irq_ctl()
+            .write(|irq_ctl| IrqConfig::default().write_to(irq_ctl))?;
+        self.muted_irq = Some(config);
+        Ok(())
+    }
+
+    /// Read the `IrqCtl` register, change it with `f` and write it back.
+    ///
+    /// Bits `f` leaves alone keep their current value, unlike [`CST816S::set_irq_config`]. Like
+    /// that one, it turns interrupts back on if they were turned off with
+    /// [`CST816S::set_interrupts_enabled`].
+    pub fn update_irq_ctl(
+        &mut self,
+        f: impl FnOnce(&mut field_sets::IrqCtl),
+    ) -> Result<(), DeviceError<I2C::Error>> {
+        let irq_ctl = self.device.irq_ctl().modify(|irq_ctl| {
+            f(irq_ctl);
+            *irq_ctl
+        })?;
+        self.update_applied_config(|applied| applied.irq = irq_ctl.into());
+        self.muted_irq = None;
+        Ok(())
+    }
+
+    /// Set the `EnTest` bit in the `IrqCtl` register, leaving the other bits as they are.
+    ///
+    /// While enabled the chip pulses the interrupt pin periodically without a touch, which
+    /// confirms the interrupt line is wired up during board bring-up. Disable it again before
+    /// reading touches, as the test pulses are indistinguishable from touch interrupts.
+    ///
+    /// Like [`CST816S::update_irq_ctl`], it ends the muting of
+    /// [`CST816S::set_interrupts_enabled`], the register then only has the bits written since.
+    pub fn set_irq_test_mode(&mut self, enable: bool) -> Result<(), DeviceError<I2C::Error>> {
+        self.update_irq_ctl(|irq_ctl| irq_ctl.set_en_test(enable))
+    }
+
+    /// Write `sources` to the `IrqCtl` register, see [`CST816S::set_irq_config`].
+    pub fn set_irq_sources(&mut self, sources: IrqSources) -> Result<(), DeviceError<I2C::Error>> {
+        self.set_irq_config(sources.into())
+    }
+
+    /// Write [`IrqConfig::touch_tracking`] to the `IrqCtl` register.
+    pub fn set_irq_touch_tracking(&mut self) -> Result<(), DeviceError<I2C::Error>> {
+        self.set_irq_config(IrqConfig::touch_tracking())
+    }
+
+    /// Write [`IrqConfig::gestures_only`] to the `IrqCtl` register.
+    pub fn set_irq_gestures_only(&mut self) -> Result<(), DeviceError<I2C::Error>> {
+        self.set_irq_config(IrqConfig::gestures_only())
+    }
+
+    /// Write [`IrqConfig::long_press_once`] to the `IrqCtl` register.
+    pub fn set_irq_long_press_once(&mut self) -> Result<(), DeviceError<I2C::Error>> {
+        self.set_irq_config(IrqConfig::long_press_once())
+    }
+
+    /// Write the low-power scanning registers.
+    pub fn set_low_power_config(
+        &mut self,
+        config: &LowPowerConfig,
+    ) -> Result<(), Error<I2C::Error>> {
+        self.device
+            .lp_scan_th()
+            .write(|m| m.set_value(config.threshold))?;
+        self.device
+            .lp_scan_win()
+            .write(|m| m.set_value(config.window))?;
+        self.device
+            .lp_scan_freq()
+            .write(|m| m.set_value(config.frequency))?;
+        self.device
+            .lp_scan_idac()
+            .write(|m| m.set_value(config.idac))?;
+        Ok(())
+    }
+
+    /// Set the normal scanning period in the `NorScanPer` register.
+    ///
+    /// The register counts in 10ms steps, so `period_ms` is rounded down to a multiple of 10ms.
+    /// Returns [`Error::OutOfRange`] outside of 10..=300ms.
+    ///
+    /// The scan period also affects the `LpAutoWakeTime` recalibration period and the
+    /// `AutoSleepTime` timeout, so check those after changing it.
+    pub fn set_scan_period(&mut self, period_ms: u16) -> Result<(), Error<I2C::Error>> {
+        let period = ScanPeriod::from_ms(period_ms).ok_or(Error::OutOfRange)?;
+        self.set_normal_scan_period(period)?;
+        Ok(())
+    }
+
+    /// Set the normal scanning period in the `NorScanPer` register, see
+    /// [`CST816S::set_scan_period`].
+    pub fn set_normal_scan_period(
+        &mut self,
+        period: ScanPeriod,
+    ) -> Result<(), DeviceError<I2C::Error>> {
+        self.device.nor_scan_per().write(|m| m.set_value(period))?;
+        self.update_applied_config(|applied| applied.scan_period = period);
+        Ok(())
+    }
+
+    /// Read the `NorScanPer` register.
+    ///
+    /// Returns [`Error::OutOfRange`] if the register holds a value outside of 1..=30.
+    pub fn normal_scan_period(&mut self) -> Result<ScanPeriod, Error<I2C::Error>> {
+        Ok(self.device.nor_scan_per().read()?.value()?)
+    }
+
+    /// Set how often the chip recalibrates in low power mode, in the `LpAutoWakeTime` register.
+    ///
+    /// While asleep the chip only wakes up when a reading crosses the `LpScanTH` threshold
+    /// relative to a baseline. Recalibrating measures that baseline again, so slow drift from
+    /// temperature, humidity or a battery running down neither causes phantom wake-ups nor
+    /// makes the panel ignore a real touch. Always-on devices whose readings drift benefit from
+    /// the shorter periods, at a small cost in current.
+    ///
+    /// [`WakeMinutes`] only accepts the documented 1..=5 minutes. The register has 3 bits, and 6
+    /// and 7 are undocumented.
+    ///
+    /// The datasheet notes that the `NorScanPer` register affects this period, without saying
+    /// how. The minutes are nominal for the default 10ms scan period; after changing it with
+    /// [`CST816S::set_scan_period`], measure the recalibration period on the device.
+    pub fn set_lp_auto_wake_time(&mut self, time: WakeMinutes) -> Result<(), Error<I2C::Error>> {
+        self.device
+            .lp_auto_wake_time()
+            .write(|m| m.set_value(time))?;
+        self.update_applied_config(|applied| applied.lp_auto_wake_time = Some(time));
+        Ok(())
+    }
+
+    /// Read the `LpAutoWakeTime` register, see [`CST816S::set_lp_auto_wake_time`].
+    ///
+    /// Returns [`Error::OutOfRange`] if the register holds a value outside of 1..=5.
+    pub fn lp_auto_wake_time(&mut self) -> Result<WakeMinutes, Error<I2C::Error>> {
+        Ok(self.device.lp_auto_wake_time().read()?.value()?)
+    }
+
+    /// Set after how many seconds of long press the chip resets itself, in the `LongPressTime`
+    /// register (0xFC). 0 disables the reset, the chip default is 10.
+    ///
+    /// The datasheet gives no upper limit, so every value is accepted and only bus errors are
+    /// returned. While a long press is held the interrupt pin keeps pulsing unless `OnceWLP` is
+    /// set in [`IrqConfig`], so with a long timeout and `OnceWLP` cleared the host sees a stream
+    /// of [`device::Gesture::LongPress`](crate::device::Gesture::LongPress) reports until the
+    /// reset.
+    pub fn set_long_press_time(&mut self, seconds: u8) -> Result<(), Error<I2C::Error>> {
+        self.device
+            .long_press_time()
+            .write(|m| m.set_value(seconds))?;
+        self.update_applied_config(|applied| applied.long_press_time_s = Some(seconds));
+        Ok(())
+    }
+
+    /// Read the `LongPressTime` register, see [`CST816S::set_long_press_time`].
+    pub fn long_press_time(&mut self) -> Result<u8, Error<I2C::Error>> {
+        Ok(self.device.long_press_time().read()?.value())
+    }
+
+    /// Set how long the chip waits without a touch before entering low power mode, in the
+    /// `AutoSleepTime` register (0xF9).
+    ///
+    /// Only has an effect while auto sleep is enabled, see [`Config::auto_sleep`]. The datasheet
+    /// counts in seconds, but notes that `NorScanPer` affects this time: the seconds are nominal
+    /// for the default 10ms scan period, so measure the timeout on the device after changing it
+    /// with [`CST816S::set_scan_period`].
+    pub fn set_auto_sleep_time(&mut self, time: SleepSeconds) -> Result<(), Error<I2C::Error>> {
+        self.device.auto_sleep_time().write(|m| m.set_value(time))?;
+        self.update_applied_config(|applied| applied.auto_sleep_time = Some(time));
+        Ok(())
+    }
+
+    /// Read the `AutoSleepTime` register, see [`CST816S::set_auto_sleep_time`].
+    ///
+    /// Returns [`Error::OutOfRange`] if the register holds 0.
+    pub fn auto_sleep_time(&mut self) -> Result<SleepSeconds, Error<I2C::Error>> {
+        Ok(self.device.auto_sleep_time().read()?.value()?)
+    }
+
+    /// Let the chip enter low power mode after [`CST816S::set_auto_sleep_time`] without a touch,
+    /// by writing 0 to the `DisAutoSleep` register (0xFE).
+    ///
+    /// Can be switched at runtime, for example on for a watch face and off for a drawing app.
+    /// A sleeping chip does not answer on the bus until it is touched, see
+    /// [`CST816S::new_polling`].
+    pub fn enable_auto_sleep(&mut self) -> Result<(), DeviceError<I2C::Error>> {
+        self.device.dis_auto_sleep().write(|m| m.set_value(0))?;
+        self.update_applied_config(|applied| applied.auto_sleep = true);
+        Ok(())
+    }
+
+    /// Keep the chip in normal scan mode, as [`CST816S::init_config`] does, by writing 0xFE to
+    /// the `DisAutoSleep` register. Any non-zero value disables auto sleep.
+    pub fn disable_auto_sleep(&mut self) -> Result<(), DeviceError<I2C::Error>> {
+        self.device.dis_auto_sleep().write(|m| m.set_value(0xFE))?;
+        self.update_applied_config(|applied| applied.auto_sleep = false);
+        Ok(())
+    }
+
+    /// Read the `DisAutoSleep` register, `true` while auto sleep is enabled.
+    pub fn is_auto_sleep_enabled(&mut self) -> Result<bool, DeviceError<I2C::Error>> {
+        Ok(self.device.dis_auto_sleep().read()?.value() == 0)
+    }
+
+    /// Set after how many seconds of touch without a valid gesture the chip resets itself, in
+    /// the `AutoReset` register (0xFB). 0 disables the reset, which is the chip default.
+    ///
+    /// Recommended for panels that lock up in the middle of a gesture. Also available as
+    /// [`Config::auto_reset_s`].
+    pub fn set_auto_reset(&mut self, seconds: u8) -> Result<(), Error<I2C::Error>> {
+        self.device.auto_reset().write(|m| m.set_value(seconds))?;
+        self.update_applied_config(|applied| applied.auto_reset_s = Some(seconds));
+        Ok(())
+    }
+
+    /// Read the `AutoReset` register, see [`CST816S::set_auto_reset`].
+    pub fn auto_reset(&mut self) -> Result<u8, Error<I2C::Error>> {
+        Ok(self.device.auto_reset().read()?.value())
+    }
+
+    /// Set the slide gesture angle in the `MotionSlAngle` register (0xEF).
+    ///
+    /// The value is `tan(c) * 10`, where `c` is the angle of the slide with respect to the
+    /// x-axis that separates left/right slides from up/down slides. Lower values classify
+    /// diagonal swipes as up/down more readily, higher values as left/right.
+    ///
+    /// A 45 degree split, `tan(45°) * 10 = 10`:
+    ///
+    /// ```
+    /// # use cst816s_device_driver::{CST816S, DEFAULT_ADDRESS};
+    /// # use embedded_hal_mock::eh1::{digital, i2c::{Mock, Transaction}};
+    /// # let mut i2c = Mock::new(&[
+    /// #     Transaction::transaction_start(0x15),
+    /// #     Transaction::write(0x15, vec![0xEF]),
+    /// #     Transaction::write(0x15, vec![10]),
+    /// #     Transaction::transaction_end(0x15),
+    /// # ]);
+    /// # let mut int_pin = digital::Mock::new(&[]);
+    /// # let mut rst_pin = digital::Mock::new(&[]);
+    /// let mut touchpad = CST816S::new(&mut i2c, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+    /// touchpad.set_motion_angle(10)?;
+    /// # i2c.done(); int_pin.done(); rst_pin.done();
+    /// # Ok::<(), cst816s_device_driver::device::DeviceError<embedded_hal::i2c::ErrorKind>>(())
+    /// ```
+    pub fn set_motion_angle(&mut self, tan_times_ten: u8) -> Result<(), DeviceError<I2C::Error>> {
+        self.device
+            .motion_sl_angle()
+            .write(|m| m.set_value(tan_times_ten))
+    }
+
+    /// Set the slide gesture angle in degrees, see [`CST816S::set_motion_angle`].
+    ///
+    /// Valid for 0-87°, larger angles return [`Error::OutOfRange`] without writing anything.
+    /// The tangent comes from an integer table and is rounded to the nearest step of 0.1, so
+    /// neighbouring small angles share a register value: 0-2° all write 0 and 45° writes 10.
+    pub fn set_slide_angle_degrees(&mut self, deg: u8) -> Result<(), Error<I2C::Error>> {
+        let tan_times_ten = *SLIDE_ANGLE_TAN_X10
+            .get(usize::from(deg))
+            .ok_or(Error::OutOfRange)?;
+        self.set_motion_angle(tan_times_ten)?;
+        Ok(())
+    }
+
+    /// Enable or disable the motion actions in the `MotionMask` register.
+    ///
+    /// Disabling double click at runtime avoids accidental double taps without a full reconfigure.
+    /// This writes every bit of the register, use [`CST816S::update_motion_mask`] to change a
+    /// single action.
+    pub fn set_motion_mask(
+        &mut self,
+        mask: MotionMaskConfig,
+    ) -> Result<(), DeviceError<I2C::Error>> {
+        self.device.motion_mask().write(|m| mask.write_to(m))?;
+        self.update_applied_config(|applied| applied.motion_mask = mask);
+        Ok(())
+    }
+
+    /// Read the `MotionMask` register, change it with `f` and write it back.
+    ///
+    /// Bits `f` leaves alone keep their current value, unlike [`CST816S::set_motion_mask`]:
+    ///
+    /// ```ignore
+    /// touchpad.update_motion_mask(|m| m.set_en_d_click(true))?;
+    /// ```
+    pub fn update_motion_mask(
+        &mut self,
+        f: impl FnOnce(&mut field_sets::MotionMask),
+    ) -> Result<(), DeviceError<I2C::Error>> {
+        let mask = self.device.motion_mask().modify(|mask| {
+            f(mask);
+            *mask
+        })?;
+        self.update_applied_config(|applied| applied.motion_mask = mask.into());
+        Ok(())
+    }
+
+    /// Read the enabled motion actions from the `MotionMask` register.
+    pub fn motion_mask(&mut self) -> Result<MotionMaskConfig, DeviceError<I2C::Error>> {
+        Ok(self.device.motion_mask().read()?.into())
+    }
+
+    /// Write the `IOCtl` register.
+    ///
+    /// - `en_1v8`: drive the I2C and interrupt pins at 1.8V instead of VDD level
+    /// - `iic_od`: use open-drain I2C pins instead of the internal pull-up resistors
+    /// - `soft_rst`: allow the host to soft reset the chip by pulling the interrupt pin low
+    ///
+    /// Enabling `soft_rst` repurposes the interrupt pin as a reset input. If the host drives or
+    /// pulls the pin low while soft reset is enabled the chip resets, so interrupt based event
+    /// reading with [`CST816S::event`] should only be combined with it when the pin is kept as
+    /// a plain input.
+    pub fn set_io_ctl(
+        &mut self,
+        en_1v8: bool,
+        iic_od: bool,
+        soft_rst: bool,
+    ) -> Result<(), DeviceError<I2C::Error>> {
+        self.device.io_ctl().write(|io_ctl| {
+            io_ctl.set_en_1_v_8(en_1v8);
+            io_ctl.set_iic_od(iic_od);
+            io_ctl.set_soft_rst(soft_rst);
+        })
+    }
+
+    /// Select the level of the I2C and interrupt pins.
+    ///
+    /// Sets the `En1v8` bit in `IOCtl`, leaving the other bits as they are. The level of the
+    /// interrupt pin changes with it, so set this right after a reset and before relying on the
+    /// interrupt level in [`CST816S::event`] or [`CST816S::wait_for_touch`].
+    pub fn set_io_voltage(&mut self, voltage: IoVoltage) -> Result<(), DeviceError<I2C::Error>> {
+        self.device
+            .io_ctl()
+            .modify(|io_ctl| io_ctl.set_en_1_v_8(voltage == IoVoltage::V1_8))
+    }
+
+    /// Read the level of the I2C and interrupt pins from the `En1v8` bit in `IOCtl`.
+    pub fn io_voltage(&mut self) -> Result<IoVoltage, DeviceError<I2C::Error>> {
+        Ok(if self.device.io_ctl().read()?.en_1_v_8() {
+            IoVoltage::V1_8
+        } else {
+            IoVoltage::Vdd
+        })
+    }
+
+    /// Enable the soft reset function of the interrupt pin.
+    ///
+    /// Sets the `SOFT_RST` bit in `IOCtl`, leaving the other bits as they are. The chip is then
+    /// reset by the host pulling the interrupt pin low, which is useful on modules that tie the
+    /// reset line to VCC. The driver only owns the interrupt pin as an input, so the pulldown has
+    /// to be done by reconfiguring the GPIO as an output in the HAL.
+    ///
+    /// While soft reset is enabled, every low level the host puts on the interrupt pin resets the
+    /// chip. To go back to normal interrupt based reading with [`CST816S::event`], set the pin
+    /// back to an input and clear the bit again with [`CST816S::set_io_ctl`].
+    pub fn soft_reset(&mut self) -> Result<(), DeviceError<I2C::Error>> {
+        self.device
+            .io_ctl()
+            .modify(|io_ctl| io_ctl.set_soft_rst(true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_hal_mock::eh1::{digital, i2c};
+    use futures_test::test;
+
+    use crate::{DEFAULT_ADDRESS, testing};
+
+    #[test]
+    async fn interrupts_enabled_restores_config() {
+        let mut bank = crate::mock::MockRegisterBank::new();
+        bank.set(0xFA, &[0b0111_0000]);
+        let mut touchpad = CST816S::new(
+            &mut bank,
+            DEFAULT_ADDRESS,
+            crate::NoIntPin,
+            crate::NoResetPin,
+        );
+
+        touchpad.set_interrupts_enabled(false).unwrap();
+        touchpad.set_interrupts_enabled(false).unwrap();
+        assert_eq!(touchpad.irq_config(), Ok(IrqConfig::default()));
+        touchpad.set_interrupts_enabled(true).unwrap();
+        assert_eq!(bank.get(0xFA), 0b0111_0000);
+
+        let mut touchpad = CST816S::new(
+            &mut bank,
+            DEFAULT_ADDRESS,
+            crate::NoIntPin,
+            crate::NoResetPin,
+        );
+        touchpad.apply(&Config::default()).unwrap();
+        let irq = touchpad.irq_config().unwrap();
+        touchpad.set_interrupts_enabled(false).unwrap();
+        assert_eq!(touchpad.needs_reconfigure(), Ok(false));
+        touchpad.set_interrupts_enabled(true).unwrap();
+        assert_eq!(touchpad.irq_config(), Ok(irq));
+        assert_eq!(touchpad.needs_reconfigure(), Ok(false));
+    }
+
+    #[test]
+    async fn irq_test_mode_ends_muting() {
+        let mut bank = crate::mock::MockRegisterBank::new();
+        let mut touchpad = CST816S::new(
+            &mut bank,
+            DEFAULT_ADDRESS,
+            crate::NoIntPin,
+            crate::NoResetPin,
+        );
+        touchpad.apply(&Config::default()).unwrap();
+        touchpad.set_auto_reapply(true);
+        touchpad.set_interrupts_enabled(false).unwrap();
+
+        touchpad.set_irq_test_mode(true).unwrap();
+        let test_only = IrqConfig {
+            en_test: true,
+            ..IrqConfig::default()
+        };
+        assert_eq!(touchpad.irq_config(), Ok(test_only));
+        assert_eq!(touchpad.needs_reconfigure(), Ok(false));
+        touchpad.auto_reapply_config().unwrap();
+        assert_eq!(touchpad.irq_config(), Ok(test_only));
+
+        // Nothing is muted anymore, so enabling leaves the register alone
+        touchpad.set_interrupts_enabled(true).unwrap();
+        assert_eq!(touchpad.irq_config(), Ok(test_only));
+    }
+
+    #[test]
+    async fn irq_config_bit_positions() {
+        let mut bank = crate::mock::MockRegisterBank::new();
+        let none = IrqConfig::default();
+        let cases = [
+            (none, 0b0000_0000),
+            (
+                IrqConfig {
+                    once_wlp: true,
+                    ..none
+                },
+                0b0000_0001,
+            ),
+            (
+                IrqConfig {
+                    en_motion: true,
+                    ..none
+                },
+                0b0001_0000,
+            ),
+            (
+                IrqConfig {
+                    en_change: true,
+                    ..none
+                },
+                0b0010_0000,
+            ),
+            (
+                IrqConfig {
+                    en_touch: true,
+                    ..none
+                },
+                0b0100_0000,
+            ),
+            (
+                IrqConfig {
+                    en_test: true,
+                    ..none
+                },
+                0b1000_0000,
+            ),
+            (
+                IrqConfig {
+                    en_touch: true,
+                    en_change: true,
+                    ..none
+                },
+                0b0110_0000,
+            ),
+            (Config::default().irq, 0b0111_0001),
+        ];
+
+        for (config, bits) in cases {
+            let mut touchpad = CST816S::new(
+                &mut bank,
+                DEFAULT_ADDRESS,
+                crate::NoIntPin,
+                crate::NoResetPin,
+            );
+            touchpad.set_irq_config(config).unwrap();
+            assert_eq!(touchpad.irq_config(), Ok(config));
+            assert_eq!(bank.get(0xFA), bits, "{config:?}");
+        }
+    }
+
+    #[test]
+    async fn irq_sources_bit_positions() {
+        let mut bank = crate::mock::MockRegisterBank::new();
+        let cases = [
+            (IrqSources::ONCE_WLP, 0x01),
+            (IrqSources::MOTION, 0x10),
+            (IrqSources::CHANGE, 0x20),
+            (IrqSources::TOUCH, 0x40),
+            (IrqSources::TEST, 0x80),
+            (IrqSources::TOUCH | IrqSources::MOTION, 0x50),
+            (IrqSources::all() - IrqSources::TEST, 0x71),
+        ];
+        for (sources, bits) in cases {
+            let mut touchpad = CST816S::new(
+                &mut bank,
+                DEFAULT_ADDRESS,
+                crate::NoIntPin,
+                crate::NoResetPin,
+            );
+            touchpad.set_irq_sources(sources).unwrap();
+            assert_eq!(bank.get(0xFA), bits);
+            assert_eq!(IrqSources::from(IrqConfig::from(sources)), sources);
+        }
+        assert_eq!(IrqSources::from_bits_truncate(0xFF), IrqSources::all());
+        assert!(IrqSources::all().contains(IrqSources::TOUCH | IrqSources::CHANGE));
+        assert!((IrqSources::TOUCH & IrqSources::MOTION).is_empty());
+    }
+
+    #[test]
+    async fn irq_presets() {
+        let mut expectations = testing::write(0xFA, &[0x60]);
+        expectations.extend(testing::write(0xFA, &[0x10]));
+        expectations.extend(testing::write(0xFA, &[0x11]));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        touchpad.set_irq_touch_tracking().unwrap();
+        touchpad.set_irq_gestures_only().unwrap();
+        touchpad.set_irq_long_press_once().unwrap();
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn irq_config_decodes_register() {
+        let mut expectations = vec![i2c::Transaction::write_read(
+            0x15,
+            vec![0xFA],
+            vec![0b0110_0001],
+        )];
+        expectations.extend(testing::write(0xFA, &[0b1001_0000]));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        let config = touchpad.irq_config().unwrap();
+        assert_eq!(
+            config,
+            IrqConfig {
+                once_wlp: true,
+                en_motion: false,
+                en_change: true,
+                en_touch: true,
+                en_test: false,
+            }
+        );
+
+        touchpad
+            .set_irq_config(IrqConfig {
+                once_wlp: false,
+                en_motion: true,
+                en_change: false,
+                en_touch: false,
+                en_test: true,
+            })
+            .unwrap();
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn slide_angle_table() {
+        let expected = [0, 2, 4, 6, 8, 12, 17, 27, 57];
+        for (i, value) in expected.into_iter().enumerate() {
+            assert_eq!(SLIDE_ANGLE_TAN_X10[i * 10], value, "{}°", i * 10);
+        }
+        assert_eq!(SLIDE_ANGLE_TAN_X10[45], 10);
+        assert_eq!(SLIDE_ANGLE_TAN_X10[87], 191);
+    }
+
+    #[test]
+    async fn set_slide_angle_degrees_writes_tangent() {
+        let mut i2c_device = i2c::Mock::new(&testing::write(0xEF, &[10]));
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        touchpad.set_slide_angle_degrees(45).unwrap();
+        assert_eq!(touchpad.set_slide_angle_degrees(88), Err(Error::OutOfRange));
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn motion_mask_bit_positions() {
+        let masks: Vec<_> = (0..8u8)
+            .map(|bits| MotionMaskConfig {
+                double_click: bits & 0b001 != 0,
+                continuous_ud: bits & 0b010 != 0,
+                continuous_lr: bits & 0b100 != 0,
+            })
+            .collect();
+        let mut expectations = Vec::new();
+        for bits in 0..8u8 {
+            expectations.extend(testing::write(0xEC, &[bits]));
+            expectations.push(i2c::Transaction::write_read(0x15, vec![0xEC], vec![bits]));
+        }
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        for mask in masks {
+            touchpad.set_motion_mask(mask).unwrap();
+            assert_eq!(touchpad.motion_mask().unwrap(), mask);
+        }
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn io_ctl_bit_positions() {
+        let mut expectations = Vec::new();
+        expectations.extend(testing::write(0xFD, &[0b001]));
+        expectations.extend(testing::write(0xFD, &[0b010]));
+        expectations.extend(testing::write(0xFD, &[0b100]));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        touchpad.set_io_ctl(true, false, false).unwrap();
+        touchpad.set_io_ctl(false, true, false).unwrap();
+        touchpad.set_io_ctl(false, false, true).unwrap();
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn soft_reset_keeps_other_io_ctl_bits() {
+        let mut expectations = vec![i2c::Transaction::write_read(0x15, vec![0xFD], vec![0b001])];
+        expectations.extend(testing::write(0xFD, &[0b101]));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        touchpad.soft_reset().unwrap();
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn io_voltage_changes_only_en_1v8() {
+        let mut expectations = vec![i2c::Transaction::write_read(0x15, vec![0xFD], vec![0b110])];
+        expectations.extend(testing::write(0xFD, &[0b111]));
+        expectations.push(i2c::Transaction::write_read(0x15, vec![0xFD], vec![0b111]));
+        expectations.push(i2c::Transaction::write_read(0x15, vec![0xFD], vec![0b111]));
+        expectations.extend(testing::write(0xFD, &[0b110]));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        touchpad.set_io_voltage(IoVoltage::V1_8).unwrap();
+        assert_eq!(touchpad.io_voltage(), Ok(IoVoltage::V1_8));
+        touchpad.set_io_voltage(IoVoltage::Vdd).unwrap();
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn low_power_config_writes_all_registers() {
+        let mut expectations = Vec::new();
+        expectations.extend(testing::write(0xF5, &[20]));
+        expectations.extend(testing::write(0xF6, &[1]));
+        expectations.extend(testing::write(0xF7, &[9]));
+        expectations.extend(testing::write(0xF8, &[2]));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        touchpad
+            .set_low_power_config(&LowPowerConfig {
+                threshold: LpScanThreshold::try_new(20).unwrap(),
+                window: LpScanWindow::try_new(1).unwrap(),
+                frequency: LpScanFrequency::try_new(9).unwrap(),
+                idac: LpScanCurrent::try_new(2).unwrap(),
+            })
+            .unwrap();
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn scan_period_in_10ms_steps() {
+        let mut i2c_device = i2c::Mock::new(&testing::write(0xEE, &[5]));
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        touchpad.set_scan_period(50).unwrap();
+        assert_eq!(touchpad.set_scan_period(9), Err(Error::OutOfRange));
+        assert_eq!(touchpad.set_scan_period(301), Err(Error::OutOfRange));
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn scan_period_bounds() {
+        assert_eq!(ScanPeriod::try_new(0), None);
+        assert_eq!(ScanPeriod::try_new(1).map(ScanPeriod::as_ms), Some(10));
+        assert_eq!(ScanPeriod::try_new(30).map(ScanPeriod::as_ms), Some(300));
+        assert_eq!(ScanPeriod::try_new(31), None);
+        assert_eq!(ScanPeriod::from_ms(0), None);
+        assert_eq!(ScanPeriod::from_ms(10), ScanPeriod::try_new(1));
+        assert_eq!(ScanPeriod::from_ms(299), ScanPeriod::try_new(29));
+        assert_eq!(ScanPeriod::from_ms(301), None);
+    }
+
+    #[test]
+    async fn normal_scan_period_round_trip() {
+        let mut bank = crate::mock::MockRegisterBank::new();
+        let mut touchpad = CST816S::new(
+            &mut bank,
+            DEFAULT_ADDRESS,
+            crate::NoIntPin,
+            crate::NoResetPin,
+        );
+
+        assert_eq!(touchpad.normal_scan_period(), Err(Error::OutOfRange));
+        let period = ScanPeriod::try_new(30).unwrap();
+        touchpad.set_normal_scan_period(period).unwrap();
+        assert_eq!(touchpad.normal_scan_period(), Ok(period));
+        assert_eq!(bank.get(0xEE), 30);
+    }
+
+    #[test]
+    async fn lp_auto_wake_time_uses_three_bits() {
+        let mut bank = crate::mock::MockRegisterBank::new();
+        bank.set(0xF4, &[0xFA]);
+        let mut touchpad = CST816S::new(
+            &mut bank,
+            DEFAULT_ADDRESS,
+            crate::NoIntPin,
+            crate::NoResetPin,
+        );
+
+        assert_eq!(touchpad.lp_auto_wake_time().map(u8::from), Ok(2));
+        for minutes in 1..=5 {
+            let time = WakeMinutes::try_new(minutes).unwrap();
+            touchpad.set_lp_auto_wake_time(time).unwrap();
+            assert_eq!(touchpad.lp_auto_wake_time(), Ok(time));
+        }
+        assert_eq!(bank.get(0xF4), 5);
+
+        bank.set(0xF4, &[0xFE]);
+        let mut touchpad = CST816S::new(
+            &mut bank,
+            DEFAULT_ADDRESS,
+            crate::NoIntPin,
+            crate::NoResetPin,
+        );
+        assert_eq!(touchpad.lp_auto_wake_time(), Err(Error::OutOfRange));
+    }
+
+    #[test]
+    async fn init_config_applies_default_config() {
+        let mut expectations = Vec::new();
+        for _ in 0..2 {
+            expectations.extend(testing::write(0xFA, &[0b0111_0001]));
+            expectations.extend(testing::write(0xEC, &[0b0000_0111]));
+            expectations.extend(testing::write(0xFE, &[0xFE]));
+            expectations.extend(testing::write(0xED, &[0x01]));
+            expectations.extend(testing::write(0xEE, &[0x01]));
+        }
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        touchpad.init_config().unwrap();
+        touchpad.apply(&Config::default()).unwrap();
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn apply_writes_custom_config() {
+        let mut expectations = Vec::new();
+        expectations.extend(testing::write(0xFA, &[0b0100_0000]));
+        expectations.extend(testing::write(0xEC, &[0b0000_0100]));
+        expectations.extend(testing::write(0xFE, &[0x00]));
+        expectations.extend(testing::write(0xED, &[20]));
+        expectations.extend(testing::write(0xEE, &[5]));
+        expectations.extend(testing::write(0xF9, &[10]));
+        expectations.extend(testing::write(0xF4, &[2]));
+        expectations.extend(testing::write(0xFC, &[0]));
+        expectations.extend(testing::write(0xFB, &[30]));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let config = Config {
+            irq: IrqConfig {
+                once_wlp: false,
+                en_motion: false,
+                en_change: false,
+                en_touch: true,
+                en_test: false,
+            },
+            motion_mask: MotionMaskConfig {
+                double_click: false,
+                continuous_ud: false,
+                continuous_lr: true,
+            },
+            irq_pulse_width: PulseWidth::new(20),
+            scan_period: ScanPeriod::from_ms(50).unwrap(),
+            auto_sleep: true,
+            auto_sleep_time: SleepSeconds::try_new(10),
+            lp_auto_wake_time: WakeMinutes::try_new(2),
+            long_press_time_s: Some(0),
+            auto_reset_s: Some(30),
+        };
+
+        let touchpad = CST816S::with_config(
+            &mut i2c_device,
+            DEFAULT_ADDRESS,
+            &mut int_pin,
+            &mut rst_pin,
+            &config,
+        );
+        assert!(touchpad.is_ok());
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn long_press_time_round_trip() {
+        let mut expectations = testing::write(0xFC, &[0]);
+        expectations.push(i2c::Transaction::write_read(
+            testing::ADDRESS,
+            vec![0xFC],
+            vec![0],
+        ));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        touchpad.set_long_press_time(0).unwrap();
+        assert_eq!(touchpad.long_press_time(), Ok(0));
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn auto_reset_round_trip() {
+        let mut expectations = testing::write(0xFB, &[5]);
+        expectations.push(i2c::Transaction::write_read(
+            testing::ADDRESS,
+            vec![0xFB],
+            vec![5],
+        ));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        touchpad.set_auto_reset(5).unwrap();
+        assert_eq!(touchpad.auto_reset(), Ok(5));
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn sleep_seconds_range() {
+        assert_eq!(SleepSeconds::try_new(0), None);
+        assert_eq!(SleepSeconds::try_new(1).map(SleepSeconds::get), Some(1));
+        assert_eq!(SleepSeconds::try_new(255).map(SleepSeconds::get), Some(255));
+    }
+
+    #[test]
+    async fn auto_sleep_time_round_trip() {
+        let mut expectations = testing::write(0xF9, &[255]);
+        expectations.push(i2c::Transaction::write_read(
+            testing::ADDRESS,
+            vec![0xF9],
+            vec![255],
+        ));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        touchpad
+            .set_auto_sleep_time(SleepSeconds::try_new(255).unwrap())
+            .unwrap();
+        assert_eq!(touchpad.auto_sleep_time().map(u8::from), Ok(255));
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn irq_test_mode_keeps_other_bits() {
+        let mut bank = crate::mock::MockRegisterBank::new();
+        bank.set(0xFA, &[0b0110_0001]);
+        let mut touchpad = CST816S::new(
+            &mut bank,
+            DEFAULT_ADDRESS,
+            crate::NoIntPin,
+            crate::NoResetPin,
+        );
+
+        touchpad.set_irq_test_mode(true).unwrap();
+        assert!(touchpad.irq_config().unwrap().en_test);
+        touchpad.set_irq_test_mode(false).unwrap();
+        assert_eq!(bank.get(0xFA), 0b0110_0001);
+    }
+
+    #[test]
+    async fn profile_register_writes() {
+        let mut expectations = Vec::new();
+        for (register, value) in [
+            (0xFA, 0x10),
+            (0xEC, 0x01),
+            (0xFE, 0x00),
+            (0xED, 10),
+            (0xEE, 10),
+            (0xF9, 2),
+            (0xF4, 5),
+            (0xF5, 48),
+            (0xF6, 3),
+            (0xF7, 7),
+            (0xF8, 1),
+            (0xFA, 0x60),
+            (0xEC, 0x07),
+            (0xFE, 0xFE),
+            (0xED, 1),
+            (0xEE, 1),
+        ] {
+            expectations.extend(testing::write(register, &[value]));
+        }
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        touchpad.apply_low_power_profile().unwrap();
+        touchpad.apply_responsive_profile().unwrap();
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn auto_reapply_rewrites_once() {
+        use core::cell::RefCell;
+        use embedded_hal_bus::i2c::RefCellDevice;
+        use embedded_hal_mock::eh1::delay::NoopDelay;
+
+        let bank = RefCell::new(crate::mock::MockRegisterBank::new());
+        let mut rst_pin = digital::Mock::new(&[
+            digital::Transaction::set(digital::State::High),
+            digital::Transaction::set(digital::State::Low),
+            digital::Transaction::set(digital::State::High),
+        ]);
+        let mut touchpad = CST816S::new(
+            RefCellDevice::new(&bank),
+            DEFAULT_ADDRESS,
+            crate::NoIntPin,
+            &mut rst_pin,
+        );
+        touchpad.apply(&Config::default()).unwrap();
+        touchpad.set_auto_reapply(true);
+        bank.borrow_mut()
+            .set(0x01, &[0x05, 1, 0x00, 0x10, 0x00, 0x20]);
+
+        // The chip reset itself, `IrqCtl` reads 0 again
+        bank.borrow_mut().set(0xFA, &[0]);
+        bank.borrow_mut().set(0xED, &[99]);
+        assert!(touchpad.event().is_some());
+        assert_eq!(bank.borrow().get(0xFA), 0b0111_0001);
+        assert_eq!(bank.borrow().get(0xED), 1);
+
+        // Nothing to do while the configuration is intact
+        bank.borrow_mut().set(0xED, &[99]);
+        assert!(touchpad.event().is_some());
+        assert_eq!(bank.borrow().get(0xED), 99);
+
+        // A reset is noticed through `DisAutoSleep` when `IrqCtl` matches the chip default
+        touchpad.set_irq_config(IrqConfig::default()).unwrap();
+        bank.borrow_mut().set(0xFE, &[0]);
+        assert!(touchpad.event().is_some());
+        assert_eq!(bank.borrow().get(0xFE), 0xFE);
+        assert_eq!(bank.borrow().get(0xED), 1);
+
+        // A reset through the pin always rewrites it
+        touchpad.reset(&mut NoopDelay::new()).unwrap();
+        assert!(touchpad.event().is_some());
+        assert_eq!(bank.borrow().get(0xED), 1);
+
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn reapply_config_after_chip_reset() {
+        use core::cell::RefCell;
+        use embedded_hal_bus::i2c::RefCellDevice;
+
+        let bank = RefCell::new(crate::mock::MockRegisterBank::new());
+        let mut touchpad = CST816S::new(
+            RefCellDevice::new(&bank),
+            DEFAULT_ADDRESS,
+            crate::NoIntPin,
+            crate::NoResetPin,
+        );
+        assert_eq!(touchpad.needs_reconfigure(), Ok(false));
+
+        touchpad.init_config().unwrap();
+        touchpad.set_irq_gestures_only().unwrap();
+        assert_eq!(touchpad.needs_reconfigure(), Ok(false));
+        assert_eq!(touchpad.reapply_config_if_reset(), Ok(false));
+
+        // AutoReset puts the registers back to their defaults
+        bank.borrow_mut().set(0xEC, &[0; CONFIG_BLOCK_LEN]);
+        assert_eq!(touchpad.needs_reconfigure(), Ok(true));
+        assert_eq!(touchpad.reapply_config_if_reset(), Ok(true));
+        assert_eq!(bank.borrow().get(0xFA), 0x10);
+        assert_eq!(bank.borrow().get(0xFE), 0xFE);
+        assert_eq!(touchpad.needs_reconfigure(), Ok(false));
+    }
+
+    #[test]
+    async fn reapply_config_keeps_runtime_changes() {
+        use core::cell::RefCell;
+        use embedded_hal_bus::i2c::RefCellDevice;
+
+        let bank = RefCell::new(crate::mock::MockRegisterBank::new());
+        let mut touchpad = CST816S::new(
+            RefCellDevice::new(&bank),
+            DEFAULT_ADDRESS,
+            crate::NoIntPin,
+            crate::NoResetPin,
+        );
+        touchpad.init_config().unwrap();
+        touchpad.set_irq_gestures_only().unwrap();
+        touchpad
+            .update_motion_mask(|m| m.set_en_con_lr(false))
+            .unwrap();
+        touchpad.enable_auto_sleep().unwrap();
+        touchpad
+            .set_auto_sleep_time(SleepSeconds::try_new(7).unwrap())
+            .unwrap();
+        touchpad.set_long_press_time(4).unwrap();
+        touchpad.set_auto_reset(3).unwrap();
+        touchpad.set_scan_period(50).unwrap();
+        touchpad
+            .set_lp_auto_wake_time(WakeMinutes::try_new(2).unwrap())
+            .unwrap();
+        touchpad.set_irq_pulse_width(PulseWidth::new(20)).unwrap();
+        let mut expected = [0; CONFIG_BLOCK_LEN];
+        touchpad.dump_config(&mut expected).unwrap();
+
+        // AutoReset puts the registers back to their defaults
+        bank.borrow_mut().set(0xEC, &[0; CONFIG_BLOCK_LEN]);
+        assert_eq!(touchpad.reapply_config_if_reset(), Ok(true));
+        let mut block = [0; CONFIG_BLOCK_LEN];
+        touchpad.dump_config(&mut block).unwrap();
+        assert_eq!(block, expected);
+        assert_eq!(bank.borrow().get(0xEC), 0b011);
+        assert_eq!(bank.borrow().get(0xED), 20);
+        assert_eq!(bank.borrow().get(0xEE), 5);
+        assert_eq!(bank.borrow().get(0xFE), 0);
+    }
+
+    #[test]
+    async fn auto_sleep_toggles() {
+        let mut bank = crate::mock::MockRegisterBank::new();
+        let mut touchpad = CST816S::new(
+            &mut bank,
+            DEFAULT_ADDRESS,
+            crate::NoIntPin,
+            crate::NoResetPin,
+        );
+
+        touchpad.disable_auto_sleep().unwrap();
+        assert_eq!(touchpad.is_auto_sleep_enabled(), Ok(false));
+        touchpad.enable_auto_sleep().unwrap();
+        assert_eq!(touchpad.is_auto_sleep_enabled(), Ok(true));
+        touchpad.disable_auto_sleep().unwrap();
+        assert_eq!(bank.get(0xFE), 0xFE);
+    }
+
+    #[test]
+    async fn dump_and_restore_config_block() {
+        let block: [u8; CONFIG_BLOCK_LEN] = core::array::from_fn(|i| i as u8 + 1);
+        let mut expectations = vec![i2c::Transaction::write_read(
+            testing::ADDRESS,
+            vec![0xEC],
+            block.to_vec(),
+        )];
+        expectations.extend(testing::write(0xEC, &block));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        let mut buf = [0; CONFIG_BLOCK_LEN];
+        touchpad.dump_config(&mut buf).unwrap();
+        assert_eq!(buf, block);
+        touchpad.restore_config(&buf).unwrap();
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn update_changes_only_the_target_bit() {
+        let mut expectations = vec![i2c::Transaction::write_read(
+            testing::ADDRESS,
+            vec![0xEC],
+            vec![0b0000_0110],
+        )];
+        expectations.extend(testing::write(0xEC, &[0b0000_0111]));
+        expectations.push(i2c::Transaction::write_read(
+            testing::ADDRESS,
+            vec![0xFA],
+            vec![0b0110_0000],
+        ));
+        expectations.extend(testing::write(0xFA, &[0b0111_0000]));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        touchpad
+            .update_motion_mask(|m| m.set_en_d_click(true))
+            .unwrap();
+        touchpad.update_irq_ctl(|m| m.set_en_motion(true)).unwrap();
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn reset_to_defaults_writes_reset_values() {
+        let writes: [(u8, u8); 12] = [
+            (0xEC, 0),
+            (0xED, 10),
+            (0xEE, 1),
+            (0xF4, 5),
+            (0xF5, 48),
+            (0xF6, 3),
+            (0xF7, 7),
+            (0xF9, 2),
+            (0xFA, 0),
+            (0xFB, 0),
+            (0xFC, 10),
+            (0xFE, 0),
+        ];
+        let expectations: Vec<_> = writes
+            .iter()
+            .flat_map(|&(register, value)| testing::write(register, &[value]))
+            .collect();
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        touchpad.reset_to_defaults().unwrap();
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn read_config_snapshot() {
+        let mut bank = crate::mock::MockRegisterBank::new();
+        let config = Config {
+            irq: IrqConfig::gestures_only(),
+            motion_mask: MotionMaskConfig {
+                double_click: true,
+                continuous_ud: false,
+                continuous_lr: true,
+            },
+            irq_pulse_width: PulseWidth::new(15),
+            scan_period: ScanPeriod::try_new(2).unwrap(),
+            auto_sleep: true,
+            auto_sleep_time: SleepSeconds::try_new(30),
+            lp_auto_wake_time: WakeMinutes::try_new(3),
+            long_press_time_s: Some(0),
+            auto_reset_s: Some(5),
+        };
+        bank.set(
+            0xEC,
+            &[
+                0b101, 15, 2, 0, 0, 0, 0, 0, 3, 48, 3, 7, 1, 30, 0x10, 5, 0, 0, 0,
+            ],
+        );
+        let mut touchpad = CST816S::new(
+            &mut bank,
+            DEFAULT_ADDRESS,
+            crate::NoIntPin,
+            crate::NoResetPin,
+        );
+        assert_eq!(touchpad.read_config(), Ok(config));
+
+        let mut touchpad = CST816S::new(
+            &mut bank,
+            DEFAULT_ADDRESS,
+            crate::NoIntPin,
+            crate::NoResetPin,
+        );
+        touchpad.apply(&Config::default()).unwrap();
+        let read = touchpad.read_config().unwrap();
+        assert_eq!(read.irq, Config::default().irq);
+        assert!(!read.auto_sleep);
+
+        bank.set(0xEE, &[31]);
+        let mut touchpad = CST816S::new(
+            &mut bank,
+            DEFAULT_ADDRESS,
+            crate::NoIntPin,
+            crate::NoResetPin,
+        );
+        assert_eq!(touchpad.read_config(), Err(Error::OutOfRange));
+    }
+
+    #[test]
+    async fn apply_config_round_trip() {
+        let mut bank = crate::mock::MockRegisterBank::new();
+        let config = Config {
+            irq: IrqConfig::touch_tracking(),
+            irq_pulse_width: PulseWidth::new(50),
+            scan_period: ScanPeriod::try_new(4).unwrap(),
+            auto_sleep: true,
+            auto_sleep_time: SleepSeconds::try_new(60),
+            lp_auto_wake_time: WakeMinutes::try_new(2),
+            long_press_time_s: Some(0),
+            auto_reset_s: Some(8),
+            ..Config::default()
+        };
+        let mut touchpad = CST816S::new(
+            &mut bank,
+            DEFAULT_ADDRESS,
+            crate::NoIntPin,
+            crate::NoResetPin,
+        );
+
+        touchpad.apply_config(&config).unwrap();
+        assert_eq!(touchpad.read_config(), Ok(config));
+    }
+
+    #[test]
+    async fn apply_config_reports_mismatch() {
+        let block = [0; CONFIG_BLOCK_LEN];
+        let mut written = block;
+        written[0x00] = 0b0000_0111;
+        written[0x01] = 1;
+        written[0x02] = 1;
+        written[0x0E] = 0b0111_0001;
+        written[0x12] = 0xFE;
+        let mut read = written;
+        // `LpScanRaw` changes all the time and is not compared
+        read[0x04] = 0x12;
+        read[0x0E] = 0;
+        let mut expectations = vec![i2c::Transaction::write_read(
+            testing::ADDRESS,
+            vec![0xEC],
+            block.to_vec(),
+        )];
+        expectations.extend(testing::write(0xEC, &written));
+        expectations.push(i2c::Transaction::write_read(
+            testing::ADDRESS,
+            vec![0xEC],
+            read.to_vec(),
+        ));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        assert_eq!(
+            touchpad.apply_config(&Config::default()),
+            Err(Error::ReadBackMismatch { register: 0xFA })
+        );
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn verify_config_lists_ignored_writes() {
+        let mut bank = crate::mock::MockRegisterBank::new();
+        let config = Config {
+            auto_sleep_time: SleepSeconds::try_new(20),
+            long_press_time_s: Some(3),
+            ..Config::default()
+        };
+        let mut touchpad = CST816S::new(
+            &mut bank,
+            DEFAULT_ADDRESS,
+            crate::NoIntPin,
+            crate::NoResetPin,
+        );
+        touchpad.apply(&config).unwrap();
+        assert!(touchpad.verify_config(&config).unwrap().is_empty());
+
+        bank.set(0xF9, &[2]);
+        bank.set(0xFE, &[0x01]);
+        let mut touchpad = CST816S::new(
+            &mut bank,
+            DEFAULT_ADDRESS,
+            crate::NoIntPin,
+            crate::NoResetPin,
+        );
+        let diff = touchpad.verify_config(&config).unwrap();
+        assert_eq!(
+            diff.mismatches(),
+            [RegisterMismatch {
+                register: 0xF9,
+                expected: 20,
+                actual: 2,
+            }]
+        );
+        assert_eq!(diff.get(0xFC), None);
+    }
+
+    #[test]
+    async fn write_config_block_is_one_read_and_one_write() {
+        let block: [u8; CONFIG_BLOCK_LEN] = core::array::from_fn(|i| i as u8 + 1);
+        let mut written = block;
+        written[0x00] = 0b0000_0111;
+        written[0x01] = 20;
+        written[0x02] = 3;
+        written[0x0D] = 10;
+        // Reserved bits of `IrqCtl` keep their value
+        written[0x0E] = 0b0110_1110;
+        written[0x10] = 4;
+        written[0x12] = 0x00;
+        let mut expectations = vec![i2c::Transaction::write_read(
+            testing::ADDRESS,
+            vec![0xEC],
+            block.to_vec(),
+        )];
+        expectations.extend(testing::write(0xEC, &written));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        let config = Config {
+            irq: IrqConfig::touch_tracking(),
+            irq_pulse_width: PulseWidth::new(20),
+            scan_period: ScanPeriod::try_new(3).unwrap(),
+            auto_sleep: true,
+            auto_sleep_time: SleepSeconds::try_new(10),
+            long_press_time_s: Some(4),
+            ..Config::default()
+        };
+        touchpad.write_config_block(&config).unwrap();
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+}
@@ -1,5 +1,5 @@
 //! # Low-Level Device Driver implementation
-use core::ops::{Deref, DerefMut};
+pub use crate::values::PulseWidth;
 
 use embedded_hal::i2c::{self as blocking_i2c, Operation, SevenBitAddress};
 use embedded_hal_async::i2c as async_i2c;
@@ -9,41 +9,88 @@ device_driver::create_device! {
   dsl: {
     config {
       type RegisterAddressType = u8;
+      type DefmtFeature = "defmt-03";
     }
     /// GestureID stores the type of gesture registered by the touch device
     register GestureId {
       type Access = RO;
       const ADDRESS = 0x01;
       const SIZE_BITS = 8;
-      value: uint as try enum Gesture {
+      const ALLOW_ADDRESS_OVERLAP = true;
+      /// Gesture reported by the chip
+      value: uint as enum Gesture {
+        /// No gesture was detected
         NoGesture = 0x00,
+        /// Finger slid up the panel
         SlideUp = 0x01,
+        /// Finger slid down the panel
         SlideDown = 0x02,
+        /// Finger slid to the left
         SlideLeft = 0x03,
+        /// Finger slid to the right
         SlideRight = 0x04,
+        /// Single tap
         SingleClick = 0x05,
         /// Double Click registered. Registration can be controlled using the [`field_sets::MotionMask`] register.
         DoubleClick = 0x0B,
         /// Long Press detected. The time to register a long press is controlled by setting
         /// the [`field_sets::LongPressTime`] register.
         LongPress = 0x0C,
+        /// Gesture code not documented for the CST816S. Some firmware revisions and
+        /// CST816 clones report additional codes, which end up here instead of failing the read.
+        Unknown = catch_all,
       } = 0..8,
     },
     /// Number of fingers
-    /// Zero or One
+    /// Zero or One, some firmware revisions report Two, see [`field_sets::SecondTouchData`]
     register FingerNum {
       type Access = RO;
       const ADDRESS = 0x02;
       const SIZE_BITS = 8;
-      value: uint = 0..1
+      const ALLOW_ADDRESS_OVERLAP = true;
+      value: uint = 0..8
     },
-    /// 4 High bits of the 12bit x-position
+    /// The full touch report from `GestureId` up to `YposL`.
+    /// This is a "virtual" register spanning 0x01 to 0x06 so the whole report can be
+    /// read in a single I2C transaction instead of one transaction per register.
+    register TouchData {
+      type Access = RO;
+      type ByteOrder = BE;
+      const ADDRESS = 0x01;
+      const ALLOW_ADDRESS_OVERLAP = true;
+      const SIZE_BITS = 48;
+
+      /// Same as [`field_sets::GestureId`]
+      gesture: uint as crate::device::Gesture = 40..48,
+      /// Same as [`field_sets::FingerNum`]
+      finger_num: uint = 32..40,
+      /// Same as the `event` field of [`field_sets::XposH`]
+      event: uint as crate::device::TouchEventType = 30..32,
+      /// Same as [`field_sets::Xpos`]
+      x: uint = 16..28,
+      /// Same as [`field_sets::Ypos`]
+      y: uint = 0..12,
+    },
+    /// 4 High bits of the 12bit x-position.
+    /// Bits 6 and 7 hold the event flag of the touch: 0b00 press down,
+    /// 0b01 lift up, 0b10 contact and 0b11 no event. Bits 4 and 5 are reserved.
     register XposH {
       type Access = RO;
       const ADDRESS = 0x03;
       const SIZE_BITS = 8;
       const ALLOW_ADDRESS_OVERLAP = true;
       value: uint = 0..4,
+      /// Whether the report is a new press, a lift or a sustained contact.
+      event: uint as enum TouchEventType {
+        /// The finger was put on the panel
+        Down = 0b00,
+        /// The finger was lifted
+        Up = 0b01,
+        /// The finger stays on the panel
+        Contact = 0b10,
+        /// No touch event
+        NoEvent = default,
+      } = 6..8,
     },
     /// 8 low bits of the 12bit x-position
     register XposL {
@@ -97,6 +144,23 @@ device_driver::create_device! {
 
       value: uint = 0..12,
     },
+    /// Second contact of the extended report, 0x07 to 0x0C.
+    /// Not in the datasheet; some firmware revisions fill it when `FingerNum` is 2, using the
+    /// layout of `XposH` to `YposL`. The last two bytes are not decoded.
+    register SecondTouchData {
+      type Access = RO;
+      type ByteOrder = BE;
+      const ADDRESS = 0x07;
+      const ALLOW_ADDRESS_OVERLAP = true;
+      const SIZE_BITS = 48;
+
+      /// Same as the `event` field of [`field_sets::XposH`]
+      event: uint as crate::device::TouchEventType = 46..48,
+      /// X-coordinate of the second contact
+      x: uint = 32..44,
+      /// Y-coordinate of the second contact
+      y: uint = 16..28,
+    },
     /// 8 high bits of the 16bit BPC0 value
     register BPC0H {
       type Access = RO;
@@ -113,6 +177,9 @@ device_driver::create_device! {
       const SIZE_BITS = 8;
       value: uint = 0..8,
     },
+    /// 16bit BPC0 value.
+    /// This is a "virtual" register combining `BPC0H` and `BPC0L`
+    /// by reading 16 bits starting from the address of `BPC0H`.
     register BPC0 {
       type Access = RO;
       type ByteOrder = BE;
@@ -138,6 +205,9 @@ device_driver::create_device! {
       const SIZE_BITS = 8;
       value: uint = 0..8,
     },
+    /// 16bit BPC1 value.
+    /// This is a "virtual" register combining `BPC1H` and `BPC1L`
+    /// by reading 16 bits starting from the address of `BPC1H`.
     register BPC1 {
       type Access = RO;
       type ByteOrder = BE;
@@ -200,7 +270,7 @@ device_driver::create_device! {
       const SIZE_BITS = 8;
       const RESET_VALUE = 10;
 
-      value: uint as crate::PulseWidth = 0..8,
+      value: uint as try crate::PulseWidth = 0..8,
     },
     /// Normal quick-scanning period
     /// This value affects [`LpAutoWakeTime`] and [`AutoSleepTime`]
@@ -212,7 +282,7 @@ device_driver::create_device! {
       const SIZE_BITS = 8;
       const RESET_VALUE = 1;
 
-      value: uint = 0..8,
+      value: uint as try crate::ScanPeriod = 0..8,
     },
     /// Gesture Detection sliding area angle control.
     /// Angle = tan(c) * 10 where c is the angle with respect to
@@ -227,6 +297,7 @@ device_driver::create_device! {
     register LpScanRaw1H {
       const ADDRESS = 0xF0;
       const SIZE_BITS = 8;
+      const ALLOW_ADDRESS_OVERLAP = true;
 
       value: uint = 0..8,
     },
@@ -234,13 +305,27 @@ device_driver::create_device! {
     register LpScanRaw1L {
       const ADDRESS = 0xF1;
       const SIZE_BITS = 8;
+      const ALLOW_ADDRESS_OVERLAP = true;
 
       value: uint = 0..8,
     },
+    /// Reference value for low-power scanning channel 1.
+    /// This is a "virtual" register combining `LpScanRaw1H` and `LpScanRaw1L`
+    /// by reading 16 bits starting from the address of `LpScanRaw1H`.
+    register LpScanRaw1 {
+      type Access = RO;
+      type ByteOrder = BE;
+      const ADDRESS = 0xF0;
+      const ALLOW_ADDRESS_OVERLAP = true;
+      const SIZE_BITS = 16;
+
+      value: uint = 0..16,
+    },
     /// High 8 bits of the reference value for low-power scanning channel 2
     register LpScanRaw2H {
       const ADDRESS = 0xF2;
       const SIZE_BITS = 8;
+      const ALLOW_ADDRESS_OVERLAP = true;
 
       value: uint = 0..8,
     },
@@ -248,9 +333,22 @@ device_driver::create_device! {
     register LpScanRaw2L {
       const ADDRESS = 0xF3;
       const SIZE_BITS = 8;
+      const ALLOW_ADDRESS_OVERLAP = true;
 
       value: uint = 0..8,
     },
+    /// Reference value for low-power scanning channel 2.
+    /// This is a "virtual" register combining `LpScanRaw2H` and `LpScanRaw2L`
+    /// by reading 16 bits starting from the address of `LpScanRaw2H`.
+    register LpScanRaw2 {
+      type Access = RO;
+      type ByteOrder = BE;
+      const ADDRESS = 0xF2;
+      const ALLOW_ADDRESS_OVERLAP = true;
+      const SIZE_BITS = 16;
+
+      value: uint = 0..16,
+    },
     /// Automatic recalibration period during low power mode.
     /// Unit: 1 minute
     /// Range: 1～5,
@@ -260,7 +358,7 @@ device_driver::create_device! {
       const SIZE_BITS = 3;
       const RESET_VALUE = 5;
 
-      value: uint = 0..3,
+      value: uint as try crate::WakeMinutes = 0..3,
     },
     /// Low power scanning wake-up threshold.
     /// The smaller it is, the more sensitive it is.
@@ -271,7 +369,7 @@ device_driver::create_device! {
       const SIZE_BITS = 8;
       const RESET_VALUE = 48;
 
-      value: uint = 0..8,
+      value: uint as try crate::LpScanThreshold = 0..8,
     },
     /// Low-power scanning range. The greater it is, the more sensitive
     /// and the more power consumption it is.
@@ -282,7 +380,7 @@ device_driver::create_device! {
       const SIZE_BITS = 2;
       const RESET_VALUE = 3;
 
-      value: uint = 0..2,
+      value: uint as try crate::LpScanWindow = 0..2,
     },
     /// Low-power scanning frequency, the smaller it is, the more sensitive it is.
     /// Range: 1-255
@@ -292,7 +390,7 @@ device_driver::create_device! {
       const SIZE_BITS = 8;
       const RESET_VALUE = 7;
 
-      value: uint = 0..8,
+      value: uint as try crate::LpScanFrequency = 0..8,
     },
     /// Low-power scanning current. The smaller it is the more sensitive it is.
     /// Range: 1-255
@@ -300,7 +398,7 @@ device_driver::create_device! {
       const ADDRESS = 0xF8;
       const SIZE_BITS = 8;
 
-      value: uint = 0..8,
+      value: uint as try crate::LpScanCurrent = 0..8,
     },
     /// Automatically enter low-power mode if there is no touch in x seconds
     /// Unit: 1 second
@@ -310,7 +408,7 @@ device_driver::create_device! {
       const SIZE_BITS = 8;
       const RESET_VALUE = 2;
 
-      value: uint = 0..8,
+      value: uint as try crate::SleepSeconds = 0..8,
     },
     /// Control when to pulse the interrupt pin low.
     /// [`EnTest`]: Interrupt pin test, automatically generates low pulses periodically after being enabled
@@ -381,6 +479,13 @@ device_driver::create_device! {
   }
 }
 
+/// Register address type of the generated [`Device`], the byte written ahead of every transfer.
+///
+/// The CST816S, CST816T and CST816D all use 8-bit register addresses. This must name the same
+/// type as `RegisterAddressType` in the register map above; the generated [`Device`] only
+/// accepts an interface with that address type, so a mismatch fails to compile.
+pub(crate) type RegisterAddress = u8;
+
 /// The `DeviceInterface<I2C>` is a struct that we will use to implement the traits supplied by the
 /// [`device-driver` crate](https://crates.io/crates/device-driver).
 pub(crate) struct DeviceInterface<I2C> {
@@ -395,12 +500,21 @@ impl<I2C> DeviceInterface<I2C> {
             device_address,
         }
     }
+
+    pub(crate) const fn device_address(&self) -> SevenBitAddress {
+        self.device_address
+    }
+
+    /// Give back the owned I2C bus.
+    pub(crate) fn release(self) -> I2C {
+        self.i2c
+    }
 }
 
 impl<BUS: blocking_i2c::I2c> device_driver::RegisterInterface for DeviceInterface<BUS> {
     type Error = DeviceError<BUS::Error>;
 
-    type AddressType = u8;
+    type AddressType = RegisterAddress;
 
     fn write_register(
         &mut self,
@@ -410,7 +524,10 @@ impl<BUS: blocking_i2c::I2c> device_driver::RegisterInterface for DeviceInterfac
     ) -> Result<(), Self::Error> {
         self.i2c.transaction(
             self.device_address,
-            &mut [Operation::Write(&[address]), Operation::Write(data)],
+            &mut [
+                Operation::Write(&address.to_be_bytes()),
+                Operation::Write(data),
+            ],
         )?;
         Ok(())
     }
@@ -421,7 +538,8 @@ impl<BUS: blocking_i2c::I2c> device_driver::RegisterInterface for DeviceInterfac
         _size_bits: u32,
         data: &mut [u8],
     ) -> Result<(), Self::Error> {
-        self.i2c.write_read(self.device_address, &[address], data)?;
+        self.i2c
+            .write_read(self.device_address, &address.to_be_bytes(), data)?;
         Ok(())
     }
 }
@@ -429,7 +547,7 @@ impl<BUS: blocking_i2c::I2c> device_driver::RegisterInterface for DeviceInterfac
 impl<BUS: async_i2c::I2c> device_driver::AsyncRegisterInterface for DeviceInterface<BUS> {
     type Error = DeviceError<BUS::Error>;
 
-    type AddressType = u8;
+    type AddressType = RegisterAddress;
 
     async fn write_register(
         &mut self,
@@ -441,7 +559,7 @@ impl<BUS: async_i2c::I2c> device_driver::AsyncRegisterInterface for DeviceInterf
             .transaction(
                 self.device_address,
                 &mut [
-                    async_i2c::Operation::Write(&[address]),
+                    async_i2c::Operation::Write(&address.to_be_bytes()),
                     async_i2c::Operation::Write(data),
                 ],
             )
@@ -456,7 +574,7 @@ impl<BUS: async_i2c::I2c> device_driver::AsyncRegisterInterface for DeviceInterf
         data: &mut [u8],
     ) -> Result<(), Self::Error> {
         self.i2c
-            .write_read(self.device_address, &[address], data)
+            .write_read(self.device_address, &address.to_be_bytes(), data)
             .await?;
         Ok(())
     }
@@ -487,47 +605,33 @@ impl<I2c> core::ops::DerefMut for DeviceError<I2c> {
     }
 }
 
-/// This is a custom conversion type for `device-driver` to use with the IrqPulseWidth register.
-#[derive(Debug)]
-pub struct PulseWidth {
-    value: u8,
-}
-
-impl PulseWidth {
-    /// Create a new `PulseWidth` instance. Asserts that the value is in the range 1-200,
-    /// as required by the chip documentation
-    pub fn new(value: u8) -> Self {
-        debug_assert!(value > 0);
-        debug_assert!(value <= 200);
-        Self { value }
+impl<I2c: core::fmt::Display> core::fmt::Display for DeviceError<I2c> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "I2C error: {}", self.0)
     }
 }
 
-impl From<u8> for PulseWidth {
-    fn from(value: u8) -> Self {
-        assert!(value > 0);
-        assert!(value <= 200);
-        Self { value }
+/// Only for bus errors that implement [`core::error::Error`] themselves, which is returned as
+/// the [`source`](core::error::Error::source).
+impl<I2c: core::error::Error + 'static> core::error::Error for DeviceError<I2c> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        Some(&self.0)
     }
 }
 
-impl From<PulseWidth> for u8 {
-    fn from(value: PulseWidth) -> Self {
-        *value
-    }
-}
-
-impl Deref for PulseWidth {
-    type Target = u8;
-
-    fn deref(&self) -> &Self::Target {
-        &self.value
-    }
-}
-
-impl DerefMut for PulseWidth {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.value
+impl core::fmt::Display for Gesture {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Gesture::NoGesture => f.write_str("no gesture"),
+            Gesture::SlideUp => f.write_str("slide up"),
+            Gesture::SlideDown => f.write_str("slide down"),
+            Gesture::SlideLeft => f.write_str("slide left"),
+            Gesture::SlideRight => f.write_str("slide right"),
+            Gesture::SingleClick => f.write_str("single click"),
+            Gesture::DoubleClick => f.write_str("double click"),
+            Gesture::LongPress => f.write_str("long press"),
+            Gesture::Unknown(code) => write!(f, "unknown gesture {code:#04x}"),
+        }
     }
 }
 
@@ -570,6 +674,77 @@ mod tests {
         assert_eq!(xh, 0x01);
         assert_eq!(xl, 0x02);
         assert_eq!(x, 0x0102);
+        assert_eq!(x, crate::combine_coord(xh, xl));
+
+        i2c_device.done();
+    }
+
+    #[test]
+    async fn read_touch_data() {
+        let mut i2c_device = i2c::Mock::new(&[i2c::Transaction::write_read(
+            0x15,
+            vec![0x01],
+            vec![0x05, 0x01, 0x80, 0xEF, 0x00, 0x78],
+        )]);
+        let mut s2 = Device::new(DeviceInterface::new(&mut i2c_device, 0x15));
+
+        let data = s2.touch_data().read().unwrap();
+
+        assert_eq!(data.gesture(), Gesture::SingleClick);
+        assert_eq!(data.finger_num(), 1);
+        assert_eq!(data.event(), TouchEventType::Contact);
+        assert_eq!(data.x(), 0xEF);
+        assert_eq!(data.y(), 0x78);
+
+        i2c_device.done();
+    }
+
+    #[test]
+    async fn read_bpc() {
+        let mut i2c_device = i2c::Mock::new(&[
+            i2c::Transaction::write_read(0x15, vec![0xB0], vec![0x12]),
+            i2c::Transaction::write_read(0x15, vec![0xB1], vec![0x34]),
+            i2c::Transaction::write_read(0x15, vec![0xB0], vec![0x12, 0x34]),
+            i2c::Transaction::write_read(0x15, vec![0xB2], vec![0xAB]),
+            i2c::Transaction::write_read(0x15, vec![0xB3], vec![0xCD]),
+            i2c::Transaction::write_read(0x15, vec![0xB2], vec![0xAB, 0xCD]),
+        ]);
+        let mut s2 = Device::new(DeviceInterface::new(&mut i2c_device, 0x15));
+
+        let h = s2.bpc_0_h().read().unwrap().value();
+        let l = s2.bpc_0_l().read().unwrap().value();
+        assert_eq!(
+            s2.bpc_0().read().unwrap().value(),
+            (h as u16) << 8 | l as u16
+        );
+        let h = s2.bpc_1_h().read().unwrap().value();
+        let l = s2.bpc_1_l().read().unwrap().value();
+        assert_eq!(
+            s2.bpc_1().read().unwrap().value(),
+            (h as u16) << 8 | l as u16
+        );
+
+        i2c_device.done();
+    }
+
+    #[test]
+    async fn read_xpos_h_event_flag() {
+        let mut i2c_device = i2c::Mock::new(&[
+            i2c::Transaction::write_read(0x15, vec![0x03], vec![0x01]),
+            i2c::Transaction::write_read(0x15, vec![0x03], vec![0x42]),
+            i2c::Transaction::write_read(0x15, vec![0x03], vec![0xC3]),
+        ]);
+        let mut s2 = Device::new(DeviceInterface::new(&mut i2c_device, 0x15));
+
+        let xpos_h = s2.xpos_h().read().unwrap();
+        assert_eq!(xpos_h.event(), TouchEventType::Down);
+        assert_eq!(xpos_h.value(), 0x1);
+        let xpos_h = s2.xpos_h().read().unwrap();
+        assert_eq!(xpos_h.event(), TouchEventType::Up);
+        assert_eq!(xpos_h.value(), 0x2);
+        let xpos_h = s2.xpos_h().read().unwrap();
+        assert_eq!(xpos_h.event(), TouchEventType::NoEvent);
+        assert_eq!(xpos_h.value(), 0x3);
 
         i2c_device.done();
     }
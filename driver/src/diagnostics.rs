@@ -0,0 +1,140 @@
+//! # Diagnostics
+use embedded_hal::i2c::I2c;
+
+use crate::{CST816S, device::DeviceError};
+
+/// Number of `BPC` reads averaged by [`CST816S::capture_baseline`].
+const BASELINE_SAMPLES: u32 = 4;
+
+/// `BPC0` and `BPC1` of the untouched panel, from [`CST816S::capture_baseline`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Baseline {
+    /// Average `BPC0` value
+    pub bpc0: u16,
+    /// Average `BPC1` value
+    pub bpc1: u16,
+}
+
+impl<I2C, TPINT, TPRST> CST816S<I2C, TPINT, TPRST>
+where
+    I2C: I2c,
+{
+    /// Read the `BPC0` and `BPC1` values.
+    ///
+    /// Returns `(bpc0, bpc1)`. These are not needed to handle touches, so reports leave them out
+    /// unless [`CST816S::set_read_bpc`] is enabled.
+    pub fn read_bpc(&mut self) -> Result<(u16, u16), DeviceError<I2C::Error>> {
+        let bpc0 = self.device.bpc_0().read()?.value();
+        let bpc1 = self.device.bpc_1().read()?.value();
+        Ok((bpc0, bpc1))
+    }
+
+    /// Average a few [`CST816S::read_bpc`] reads into a [`Baseline`] for
+    /// [`CST816S::touch_confidence`].
+    ///
+    /// Must be called while nothing touches the panel, for example right after
+    /// [`CST816S::init`] or from a calibration screen asking the user to let go. A baseline
+    /// captured during a touch makes real touches look like noise. Capture it again now and then
+    /// on panels that drift with temperature.
+    pub fn capture_baseline(&mut self) -> Result<Baseline, DeviceError<I2C::Error>> {
+        let (mut bpc0, mut bpc1) = (0, 0);
+        for _ in 0..BASELINE_SAMPLES {
+            let (sample0, sample1) = self.read_bpc()?;
+            bpc0 += u32::from(sample0);
+            bpc1 += u32::from(sample1);
+        }
+        Ok(Baseline {
+            bpc0: (bpc0 / BASELINE_SAMPLES) as u16,
+            bpc1: (bpc1 / BASELINE_SAMPLES) as u16,
+        })
+    }
+
+    /// How far the live `BPC0` and `BPC1` values are from `baseline`, as the sum of both
+    /// differences.
+    ///
+    /// Near 0 means the panel looks untouched. A cheap check whether a report with a finger
+    /// comes from a real touch or from noise; the threshold depends on the panel and is best
+    /// found by logging this value for real touches and for an idle panel.
+    pub fn touch_confidence(
+        &mut self,
+        baseline: &Baseline,
+    ) -> Result<u16, DeviceError<I2C::Error>> {
+        let (bpc0, bpc1) = self.read_bpc()?;
+        Ok(bpc0
+            .abs_diff(baseline.bpc0)
+            .saturating_add(bpc1.abs_diff(baseline.bpc1)))
+    }
+
+    /// Read the reference values of the two low-power scanning channels.
+    ///
+    /// Returns `(channel_1, channel_2)` from the `LpScanRaw1` and `LpScanRaw2` register pairs.
+    /// Dumping these helps to find out why wake-on-touch stopped working on a device.
+    pub fn read_lp_scan_raw(&mut self) -> Result<(u16, u16), DeviceError<I2C::Error>> {
+        let channel_1 = self.device.lp_scan_raw_1().read()?.value();
+        let channel_2 = self.device.lp_scan_raw_2().read()?.value();
+        Ok((channel_1, channel_2))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DEFAULT_ADDRESS, testing};
+    use embedded_hal_mock::eh1::{digital, i2c};
+    use futures_test::test;
+
+    #[test]
+    async fn baseline_and_confidence() {
+        let bpc = |bpc0: u16, bpc1: u16| {
+            [
+                i2c::Transaction::write_read(0x15, vec![0xB0], bpc0.to_be_bytes().to_vec()),
+                i2c::Transaction::write_read(0x15, vec![0xB2], bpc1.to_be_bytes().to_vec()),
+            ]
+        };
+        let mut expectations = Vec::new();
+        for (bpc0, bpc1) in [(100, 200), (102, 198), (98, 202), (100, 200)] {
+            expectations.extend(bpc(bpc0, bpc1));
+        }
+        expectations.extend(bpc(101, 199));
+        expectations.extend(bpc(40, 260));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        let baseline = touchpad.capture_baseline().unwrap();
+        assert_eq!(
+            baseline,
+            Baseline {
+                bpc0: 100,
+                bpc1: 200
+            }
+        );
+        assert_eq!(touchpad.touch_confidence(&baseline).unwrap(), 2);
+        assert_eq!(touchpad.touch_confidence(&baseline).unwrap(), 120);
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn read_lp_scan_raw_combines_bytes() {
+        let mut i2c_device = i2c::Mock::new(&[
+            i2c::Transaction::write_read(testing::ADDRESS, vec![0xF0], vec![0x12, 0x34]),
+            i2c::Transaction::write_read(testing::ADDRESS, vec![0xF2], vec![0xAB, 0xCD]),
+        ]);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        assert_eq!(touchpad.read_lp_scan_raw().unwrap(), (0x1234, 0xABCD));
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+}
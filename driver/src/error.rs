@@ -0,0 +1,126 @@
+//! # High-level driver errors
+use embedded_hal::{digital, i2c};
+
+use crate::{ValueOutOfRange, device::DeviceError};
+
+/// Error returned by the high-level [`CST816S`](crate::CST816S) driver.
+///
+/// Pin errors are reduced to their [`digital::ErrorKind`] so the interrupt and reset pins
+/// can have different error types without adding more type parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum Error<I2c> {
+    /// The I2C bus returned an error
+    I2c(I2c),
+    /// Reading or driving one of the GPIO pins failed
+    Pin(digital::ErrorKind),
+    /// A configuration value was outside the range allowed by the datasheet
+    OutOfRange,
+    /// The `ChipId` register holds a value that does not belong to a known chip variant
+    ///
+    /// Usually another chip answering at the address, like a CST328, or a mis-wired bus reading
+    /// 0x00 or 0xFF.
+    WrongChipId {
+        /// The `ChipId` values of the supported variants
+        expected: &'static [u8],
+        /// The value read
+        found: u8,
+    },
+    /// The chip did not get ready within the timeout
+    Timeout,
+    /// A register read back a different value than was written, see
+    /// [`CST816S::apply_config`](crate::CST816S::apply_config)
+    ReadBackMismatch {
+        /// Address of the first register that differs
+        register: u8,
+    },
+}
+
+impl<I2c> From<DeviceError<I2c>> for Error<I2c> {
+    fn from(value: DeviceError<I2c>) -> Self {
+        Self::I2c(value.0)
+    }
+}
+
+impl<I2c> From<ValueOutOfRange> for Error<I2c> {
+    fn from(_: ValueOutOfRange) -> Self {
+        Self::OutOfRange
+    }
+}
+
+impl<I2c: core::fmt::Display> core::fmt::Display for Error<I2c> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::I2c(e) => write!(f, "I2C error: {e}"),
+            Self::Pin(kind) => write!(f, "pin error: {kind}"),
+            Self::OutOfRange => f.write_str("value out of range"),
+            Self::WrongChipId { found, .. } => write!(f, "unknown chip id {found:#04x}"),
+            Self::Timeout => f.write_str("chip not ready before the timeout"),
+            Self::ReadBackMismatch { register } => {
+                write!(f, "register {register:#04x} did not keep the written value")
+            }
+        }
+    }
+}
+
+/// Only for bus errors that implement [`core::error::Error`] themselves, which is returned as
+/// the [`source`](core::error::Error::source) of [`Error::I2c`].
+impl<I2c: core::error::Error + 'static> core::error::Error for Error<I2c> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::I2c(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl<I2c> Error<I2c> {
+    pub(crate) fn pin(error: impl digital::Error) -> Self {
+        Self::Pin(error.kind())
+    }
+}
+
+/// Whether the chip did not acknowledge a transfer, which is what it does while asleep.
+pub(crate) fn is_nack(error: &impl i2c::Error) -> bool {
+    matches!(error.kind(), i2c::ErrorKind::NoAcknowledge(_))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::error::Error as _;
+
+    #[derive(Debug, PartialEq)]
+    struct BusError;
+
+    impl core::fmt::Display for BusError {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.write_str("bus fault")
+        }
+    }
+
+    impl core::error::Error for BusError {}
+
+    fn boxed(error: DeviceError<BusError>) -> Result<(), Box<dyn core::error::Error>> {
+        Err(error)?
+    }
+
+    #[test]
+    fn source_is_the_bus_error() {
+        let error = boxed(DeviceError(BusError)).unwrap_err();
+        assert_eq!(error.to_string(), "I2C error: bus fault");
+        assert!(error.source().unwrap().is::<BusError>());
+
+        let error = Error::from(DeviceError(BusError));
+        assert!(error.source().unwrap().is::<BusError>());
+        assert!(Error::<BusError>::Timeout.source().is_none());
+        assert_eq!(
+            Error::<BusError>::WrongChipId {
+                expected: &[0xB4],
+                found: 0xFF
+            }
+            .to_string(),
+            "unknown chip id 0xff"
+        );
+    }
+}
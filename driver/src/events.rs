@@ -0,0 +1,55 @@
+//! # Draining event iterator
+use embedded_hal::i2c::I2c;
+
+use crate::{CST816S, Error, TouchEvent, TouchReady};
+
+/// Iterator returned by [`CST816S::events`].
+///
+/// Yields a report for every read while the interrupt pin stays asserted, and stops once the
+/// pin goes high or the maximum number of reads is reached. The limit protects against a
+/// stuck interrupt line turning the loop into a livelock.
+pub struct Events<'a, I2C, TPINT, TPRST> {
+    driver: &'a mut CST816S<I2C, TPINT, TPRST>,
+    remaining: usize,
+}
+
+impl<'a, I2C, TPINT, TPRST> Events<'a, I2C, TPINT, TPRST> {
+    pub(crate) fn new(driver: &'a mut CST816S<I2C, TPINT, TPRST>, max_reads: usize) -> Self {
+        Self {
+            driver,
+            remaining: max_reads,
+        }
+    }
+}
+
+impl<I2C, TPINT, TPRST> Iterator for Events<'_, I2C, TPINT, TPRST>
+where
+    I2C: I2c,
+    TPINT: TouchReady,
+{
+    type Item = Result<TouchEvent, Error<I2C::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.remaining > 0 {
+            match self.driver.interrupt_pending() {
+                Ok(true) => {}
+                Ok(false) => return None,
+                Err(e) => {
+                    self.remaining = 0;
+                    return Some(Err(e));
+                }
+            }
+            self.remaining -= 1;
+            match self.driver.read_report() {
+                Ok(Some(event)) => {
+                    self.driver.acknowledge();
+                    return Some(Ok(event));
+                }
+                // Dropped by the origin glitch filter, counts against the limit
+                Ok(None) => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,586 @@
+//! # Panel geometry
+use crate::{CST816S, TouchPoint, device::Gesture, regions::Rect};
+
+/// What to do with reported coordinates outside of the panel set with
+/// [`CST816S::set_panel_size`].
+///
+/// The raw coordinates are 12 bit, and some panels report values past their edge, especially
+/// when the finger is lifted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum BoundsPolicy {
+    /// Report the coordinates as read
+    #[default]
+    PassThrough,
+    /// Move the point to the nearest edge of the panel
+    Clamp,
+    /// Drop the report, no event is produced for it
+    Reject,
+}
+
+/// Number of valid bits in the reported coordinates.
+///
+/// Set with [`CST816S::set_coordinate_width`]. Some smaller panels only report 10 bit
+/// coordinates and use the upper bits of `XposH` and `YposH` for flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum CoordinateWidth {
+    /// 10 bit coordinates, 0 to 1023
+    Bits10,
+    /// 12 bit coordinates, 0 to 4095, as in the datasheet
+    #[default]
+    Bits12,
+}
+
+impl CoordinateWidth {
+    /// Mask of the valid coordinate bits.
+    pub const fn mask(self) -> u16 {
+        match self {
+            Self::Bits10 => 0x03FF,
+            Self::Bits12 => 0x0FFF,
+        }
+    }
+}
+
+/// How the display is mounted relative to the native axes of the touch panel.
+///
+/// Set with [`CST816S::set_orientation`], the driver then reports points in display space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum Orientation {
+    /// Display and panel axes match
+    #[default]
+    Deg0,
+    /// Display rotated 90° clockwise, the panel's left edge is the display's top edge
+    Deg90,
+    /// Display rotated 180°
+    Deg180,
+    /// Display rotated 270° clockwise, the panel's right edge is the display's top edge
+    Deg270,
+}
+
+impl<I2C, TPINT, TPRST> CST816S<I2C, TPINT, TPRST> {
+    /// Tell the driver the panel size in pixels, for example `(240, 240)`.
+    ///
+    /// Valid coordinates are `0..width` and `0..height`. What happens to points outside of
+    /// that is picked with [`CST816S::set_bounds_policy`].
+    pub fn set_panel_size(&mut self, width: u16, height: u16) {
+        self.panel_size = Some((width, height));
+    }
+
+    /// Pick how out of range coordinates are handled, [`BoundsPolicy::PassThrough`] by default.
+    ///
+    /// Has no effect until [`CST816S::set_panel_size`] was called.
+    pub fn set_bounds_policy(&mut self, policy: BoundsPolicy) {
+        self.bounds_policy = policy;
+    }
+
+    /// Hold the reported point still until the finger moves at least `px` pixels.
+    ///
+    /// Some panels report coordinates that jitter by a few pixels under a finger held still.
+    /// With a threshold set, every [`TouchEvent`](crate::TouchEvent) whose point is less than
+    /// `px` pixels (Chebyshev distance) away from the last reported point carries that last point
+    /// instead. This is software filtering in the driver, the chip is not reconfigured. The
+    /// default of 0 turns it off; [`CST816S::read_touch_data`] always returns the raw
+    /// coordinates.
+    pub fn set_debounce(&mut self, px: u16) {
+        self.debounce = px;
+        self.stable_point = None;
+    }
+
+    /// The square reaching `radius` pixels around the last reported point, for partial redraws.
+    ///
+    /// The point is the last one from [`CST816S::event`] and the other reading methods, or from
+    /// [`CST816S::poll`], and is kept after the finger is lifted. The square is cut off at the
+    /// display edges, using [`CST816S::set_panel_size`] turned by [`CST816S::set_orientation`]
+    /// if it was called. Returns `None` before the first touch.
+    pub fn last_touch_region(&self, radius: u16) -> Option<Rect> {
+        let TouchPoint { x, y } = self.last_point?;
+        let (right, bottom) = match self.display_size() {
+            Some((width, height)) => (width.saturating_sub(1), height.saturating_sub(1)),
+            None => (u16::MAX, u16::MAX),
+        };
+        Some(Rect {
+            min: TouchPoint::new(x.saturating_sub(radius), y.saturating_sub(radius)),
+            max: TouchPoint::new(
+                x.saturating_add(radius).min(right),
+                y.saturating_add(radius).min(bottom),
+            ),
+        })
+    }
+
+    /// Rotate reported points from the panel's native axes into display space.
+    ///
+    /// Uses the size from [`CST816S::set_panel_size`], given in native panel axes, and has no
+    /// effect until it was called. The bounds policy is applied before rotating. Gestures are
+    /// reported as detected by the chip unless [`CST816S::set_rotate_gestures`] is enabled.
+    pub fn set_orientation(&mut self, orientation: Orientation) {
+        self.orientation = orientation;
+    }
+
+    /// Pick how many bits of the reported coordinates are used, [`CoordinateWidth::Bits12`] by
+    /// default.
+    ///
+    /// With [`CoordinateWidth::Bits10`] the upper bits are masked off before any other
+    /// handling, so flags set there by 10 bit panels don't show up as coordinates past the
+    /// panel edge.
+    pub fn set_coordinate_width(&mut self, width: CoordinateWidth) {
+        self.coordinate_width = width;
+    }
+
+    /// Build a point from the raw coordinate registers, masked to the coordinate width.
+    pub(crate) fn raw_point(&self, x: u16, y: u16) -> TouchPoint {
+        let mask = self.coordinate_width.mask();
+        TouchPoint::new(x & mask, y & mask)
+    }
+
+    /// Rotate slide gestures along with the points, so they match the directions on the display.
+    ///
+    /// With [`Orientation::Deg90`], a slide towards the panel's top arrives as
+    /// [`Gesture::SlideRight`]. Clicks, long presses and unknown codes are not changed. Unlike
+    /// the points this does not need a panel size. Off by default.
+    pub fn set_rotate_gestures(&mut self, enabled: bool) {
+        self.rotate_gestures = enabled;
+    }
+
+    /// Map a gesture to display space if [`CST816S::set_rotate_gestures`] is enabled.
+    pub(crate) fn to_display_gesture(&self, gesture: Gesture) -> Gesture {
+        if self.rotate_gestures {
+            rotate_gesture(gesture, self.orientation)
+        } else {
+            gesture
+        }
+    }
+
+    /// Panel size in display space, width and height swapped for a display turned by 90°.
+    fn display_size(&self) -> Option<(u16, u16)> {
+        let (width, height) = self.panel_size?;
+        Some(match self.orientation {
+            Orientation::Deg0 | Orientation::Deg180 => (width, height),
+            Orientation::Deg90 | Orientation::Deg270 => (height, width),
+        })
+    }
+
+    /// Map a raw point to display space, returning `None` if it is rejected.
+    pub(crate) fn to_display(&self, raw: TouchPoint) -> Option<TouchPoint> {
+        let point = self.apply_bounds(raw)?;
+        let Some((width, height)) = self.panel_size else {
+            return Some(point);
+        };
+        let right = width.saturating_sub(1);
+        let bottom = height.saturating_sub(1);
+        let TouchPoint { x, y } = point;
+        Some(match self.orientation {
+            Orientation::Deg0 => point,
+            Orientation::Deg90 => TouchPoint::new(bottom.saturating_sub(y), x),
+            Orientation::Deg180 => {
+                TouchPoint::new(right.saturating_sub(x), bottom.saturating_sub(y))
+            }
+            Orientation::Deg270 => TouchPoint::new(y, right.saturating_sub(x)),
+        })
+    }
+
+    /// Apply the debounce threshold to a point about to be reported.
+    pub(crate) fn debounced(&self, point: TouchPoint) -> TouchPoint {
+        match self.stable_point {
+            Some(stable) if stable.chebyshev_distance(point) < self.debounce => stable,
+            _ => point,
+        }
+    }
+
+    /// Remember a reported point as the one [`CST816S::set_debounce`] holds on to.
+    pub(crate) fn hold_point(&mut self, point: TouchPoint) {
+        if self.debounce != 0 {
+            self.stable_point = Some(point);
+        }
+    }
+
+    /// Apply the bounds policy to a point, returning `None` if it is rejected.
+    fn apply_bounds(&self, point: TouchPoint) -> Option<TouchPoint> {
+        let Some((width, height)) = self.panel_size else {
+            return Some(point);
+        };
+        let TouchPoint { x, y } = point;
+        let inside = x < width && y < height;
+        match self.bounds_policy {
+            BoundsPolicy::PassThrough => Some(point),
+            BoundsPolicy::Clamp => Some(TouchPoint::new(
+                x.min(width.saturating_sub(1)),
+                y.min(height.saturating_sub(1)),
+            )),
+            BoundsPolicy::Reject if inside => Some(point),
+            BoundsPolicy::Reject => None,
+        }
+    }
+}
+
+/// Rotate a slide from the panel's native axes into display space.
+fn rotate_gesture(gesture: Gesture, orientation: Orientation) -> Gesture {
+    use Gesture::{SlideDown, SlideLeft, SlideRight, SlideUp};
+    match (orientation, gesture) {
+        (Orientation::Deg90, SlideUp) | (Orientation::Deg270, SlideDown) => SlideRight,
+        (Orientation::Deg90, SlideDown) | (Orientation::Deg270, SlideUp) => SlideLeft,
+        (Orientation::Deg90, SlideLeft) | (Orientation::Deg270, SlideRight) => SlideUp,
+        (Orientation::Deg90, SlideRight) | (Orientation::Deg270, SlideLeft) => SlideDown,
+        (Orientation::Deg180, SlideUp) => SlideDown,
+        (Orientation::Deg180, SlideDown) => SlideUp,
+        (Orientation::Deg180, SlideLeft) => SlideRight,
+        (Orientation::Deg180, SlideRight) => SlideLeft,
+        _ => gesture,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DEFAULT_ADDRESS, testing};
+    use embedded_hal_mock::eh1::{
+        digital::{self, State},
+        i2c,
+    };
+    use futures_test::test;
+
+    fn read_points(policy: BoundsPolicy) -> Vec<Option<TouchPoint>> {
+        let payloads = [(100, 200), (239, 239), (240, 100), (273, 300)];
+        let expectations: Vec<_> = payloads
+            .iter()
+            .flat_map(|&(x, y)| testing::report(0x00, x, y))
+            .collect();
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin =
+            digital::Mock::new(&vec![digital::Transaction::get(State::Low); payloads.len()]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+        touchpad.set_panel_size(240, 240);
+        touchpad.set_bounds_policy(policy);
+
+        let points = payloads
+            .iter()
+            .map(|_| touchpad.event().map(|event| event.point))
+            .collect();
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+        points
+    }
+
+    #[test]
+    async fn pass_through_keeps_points() {
+        assert_eq!(
+            read_points(BoundsPolicy::PassThrough),
+            [
+                Some(TouchPoint::new(100, 200)),
+                Some(TouchPoint::new(239, 239)),
+                Some(TouchPoint::new(240, 100)),
+                Some(TouchPoint::new(273, 300))
+            ]
+        );
+    }
+
+    #[test]
+    async fn clamp_moves_points_to_edge() {
+        assert_eq!(
+            read_points(BoundsPolicy::Clamp),
+            [
+                Some(TouchPoint::new(100, 200)),
+                Some(TouchPoint::new(239, 239)),
+                Some(TouchPoint::new(239, 100)),
+                Some(TouchPoint::new(239, 239))
+            ]
+        );
+    }
+
+    #[test]
+    async fn reject_drops_reports() {
+        assert_eq!(
+            read_points(BoundsPolicy::Reject),
+            [
+                Some(TouchPoint::new(100, 200)),
+                Some(TouchPoint::new(239, 239)),
+                None,
+                None
+            ]
+        );
+    }
+
+    #[test]
+    async fn ten_bit_coordinates_drop_flag_bits() {
+        let mut bank = crate::mock::MockRegisterBank::new();
+        // Contact flag plus two flag bits above a 10 bit x of 0x220, y of 0x0F0 with flag 0x400
+        bank.set(0x01, &[0x00, 1, 0b1000_1110, 0x20, 0x04, 0xF0]);
+
+        let mut touchpad = CST816S::new_polling(&mut bank, DEFAULT_ADDRESS, ());
+        assert_eq!(
+            touchpad.event().unwrap().point,
+            TouchPoint::new(0xE20, 0x4F0)
+        );
+
+        let mut touchpad = CST816S::new_polling(&mut bank, DEFAULT_ADDRESS, ());
+        touchpad.set_coordinate_width(CoordinateWidth::Bits10);
+        assert_eq!(
+            touchpad.event().unwrap().point,
+            TouchPoint::new(0x220, 0x0F0)
+        );
+    }
+
+    #[test]
+    async fn debounce_starts_fresh_after_lift() {
+        // Down at (100, 100), lift there, then a second tap 2px away
+        let reports = [
+            [0x00, 1, 0x00, 100, 0x00, 100],
+            [0x00, 1, 0b0100_0000, 100, 0x00, 100],
+            [0x00, 1, 0x00, 102, 0x00, 101],
+        ];
+        let expectations: Vec<_> = reports
+            .iter()
+            .map(|report| {
+                i2c::Transaction::write_read(testing::ADDRESS, vec![0x01], report.to_vec())
+            })
+            .collect();
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin =
+            digital::Mock::new(&vec![digital::Transaction::get(State::Low); reports.len()]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+        touchpad.set_debounce(4);
+
+        assert_eq!(touchpad.event().unwrap().point, TouchPoint::new(100, 100));
+        assert_eq!(touchpad.event().unwrap().point, TouchPoint::new(100, 100));
+        assert_eq!(touchpad.event().unwrap().point, TouchPoint::new(102, 101));
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn debounce_holds_jittery_points() {
+        let payloads = [(100, 100), (102, 99), (98, 101), (110, 100), (111, 100)];
+        let expectations: Vec<_> = payloads
+            .iter()
+            .flat_map(|&(x, y)| testing::report(0x00, x, y))
+            .collect();
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin =
+            digital::Mock::new(&vec![digital::Transaction::get(State::Low); payloads.len()]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+        touchpad.set_debounce(4);
+
+        let points: Vec<_> = payloads
+            .iter()
+            .map(|_| touchpad.event().unwrap().point)
+            .collect();
+        assert_eq!(
+            points,
+            [
+                TouchPoint::new(100, 100),
+                TouchPoint::new(100, 100),
+                TouchPoint::new(100, 100),
+                TouchPoint::new(110, 100),
+                TouchPoint::new(110, 100)
+            ]
+        );
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    fn read_oriented(orientation: Orientation) -> Vec<TouchPoint> {
+        let payloads = [(0, 0), (239, 279), (10, 20), (300, 300)];
+        let expectations: Vec<_> = payloads
+            .iter()
+            .flat_map(|&(x, y)| testing::report(0x00, x, y))
+            .collect();
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin =
+            digital::Mock::new(&vec![digital::Transaction::get(State::Low); payloads.len()]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+        touchpad.set_panel_size(240, 280);
+        touchpad.set_bounds_policy(BoundsPolicy::Clamp);
+        touchpad.set_orientation(orientation);
+
+        let points = payloads
+            .iter()
+            .map(|_| touchpad.event().unwrap().point)
+            .collect();
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+        points
+    }
+
+    #[test]
+    async fn orientation_deg0_keeps_points() {
+        assert_eq!(
+            read_oriented(Orientation::Deg0),
+            [
+                TouchPoint::new(0, 0),
+                TouchPoint::new(239, 279),
+                TouchPoint::new(10, 20),
+                TouchPoint::new(239, 279)
+            ]
+        );
+    }
+
+    #[test]
+    async fn orientation_deg90() {
+        assert_eq!(
+            read_oriented(Orientation::Deg90),
+            [
+                TouchPoint::new(279, 0),
+                TouchPoint::new(0, 239),
+                TouchPoint::new(259, 10),
+                TouchPoint::new(0, 239)
+            ]
+        );
+    }
+
+    #[test]
+    async fn orientation_deg180() {
+        assert_eq!(
+            read_oriented(Orientation::Deg180),
+            [
+                TouchPoint::new(239, 279),
+                TouchPoint::new(0, 0),
+                TouchPoint::new(229, 259),
+                TouchPoint::new(0, 0)
+            ]
+        );
+    }
+
+    #[test]
+    async fn orientation_deg270() {
+        assert_eq!(
+            read_oriented(Orientation::Deg270),
+            [
+                TouchPoint::new(0, 239),
+                TouchPoint::new(279, 0),
+                TouchPoint::new(20, 229),
+                TouchPoint::new(279, 0)
+            ]
+        );
+    }
+
+    #[test]
+    async fn gesture_rotation_matrix() {
+        use Gesture::{SlideDown as D, SlideLeft as L, SlideRight as R, SlideUp as U};
+        let slides = [U, D, L, R];
+        let table = [
+            (Orientation::Deg0, [U, D, L, R]),
+            (Orientation::Deg90, [R, L, U, D]),
+            (Orientation::Deg180, [D, U, R, L]),
+            (Orientation::Deg270, [L, R, D, U]),
+        ];
+        for (orientation, expected) in table {
+            for (slide, rotated) in slides.iter().zip(expected) {
+                assert_eq!(
+                    rotate_gesture(*slide, orientation),
+                    rotated,
+                    "{slide:?} at {orientation:?}"
+                );
+            }
+            for other in [
+                Gesture::NoGesture,
+                Gesture::SingleClick,
+                Gesture::DoubleClick,
+                Gesture::LongPress,
+                Gesture::Unknown(0x07),
+            ] {
+                assert_eq!(rotate_gesture(other, orientation), other);
+            }
+        }
+    }
+
+    #[test]
+    async fn rotate_gestures_is_opt_in() {
+        let mut i2c_device = i2c::Mock::new(
+            &[testing::report(0x01, 10, 10), testing::report(0x01, 10, 10)].concat(),
+        );
+        let mut int_pin = digital::Mock::new(&vec![digital::Transaction::get(State::Low); 2]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+        touchpad.set_orientation(Orientation::Deg90);
+
+        assert_eq!(touchpad.event().unwrap().gesture, Gesture::SlideUp);
+        touchpad.set_rotate_gestures(true);
+        assert_eq!(touchpad.event().unwrap().gesture, Gesture::SlideRight);
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn last_touch_region_around_point() {
+        let mut i2c_device = i2c::Mock::new(
+            &[
+                testing::report(0x05, 100, 5),
+                testing::report(0x05, 235, 120),
+            ]
+            .concat(),
+        );
+        let mut int_pin = digital::Mock::new(&vec![digital::Transaction::get(State::Low); 2]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+        touchpad.set_panel_size(240, 240);
+
+        assert_eq!(touchpad.last_touch_region(10), None);
+        touchpad.event().unwrap();
+        assert_eq!(
+            touchpad.last_touch_region(10),
+            Some(Rect {
+                min: TouchPoint::new(90, 0),
+                max: TouchPoint::new(110, 15),
+            })
+        );
+        touchpad.event().unwrap();
+        touchpad.take_last_event();
+        assert_eq!(
+            touchpad.last_touch_region(10),
+            Some(Rect {
+                min: TouchPoint::new(225, 110),
+                max: TouchPoint::new(239, 130),
+            })
+        );
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn last_touch_region_rotated() {
+        let mut i2c_device = i2c::Mock::new(&testing::report(0x05, 100, 5));
+        let mut int_pin = digital::Mock::new(&[digital::Transaction::get(State::Low)]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+        touchpad.set_panel_size(240, 280);
+        touchpad.set_orientation(Orientation::Deg90);
+
+        assert_eq!(touchpad.event().unwrap().point, TouchPoint::new(274, 100));
+        // The display is 280 wide and 240 high
+        assert_eq!(
+            touchpad.last_touch_region(10),
+            Some(Rect {
+                min: TouchPoint::new(264, 90),
+                max: TouchPoint::new(279, 110),
+            })
+        );
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+}
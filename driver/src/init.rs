@@ -0,0 +1,148 @@
+//! # Bring-up in one call
+use embedded_hal::{delay::DelayNs, digital::OutputPin, i2c::I2c};
+
+use crate::{CST816S, Config, Error, FirmwareInfo, ResetTimings};
+
+/// How long [`CST816S::init`] waits for the chip to answer after the reset.
+pub const READY_TIMEOUT_MS: u32 = 100;
+
+impl<I2C, TPINT, TPRST> CST816S<I2C, TPINT, TPRST>
+where
+    I2C: I2c,
+    TPRST: OutputPin,
+{
+    /// Reset the chip, check it and write the default [`Config`].
+    ///
+    /// See [`CST816S::init_with`].
+    pub fn init(&mut self, delay: &mut impl DelayNs) -> Result<FirmwareInfo, Error<I2C::Error>> {
+        self.init_with(delay, &Config::default())
+    }
+
+    /// Reset the chip, check it and write `config`.
+    ///
+    /// Runs [`CST816S::reset`], waits up to [`READY_TIMEOUT_MS`] for the chip to answer with
+    /// [`CST816S::wait_until_ready`], switches the driver to the detected
+    /// [`ChipVariant`](crate::ChipVariant) and finally [`CST816S::apply`]s `config`. Returns the
+    /// identification registers read on the way.
+    ///
+    /// A chip answering with an unknown `ChipId` fails with [`Error::WrongChipId`], a chip that
+    /// does not answer at all with the bus error of the last attempt.
+    pub fn init_with(
+        &mut self,
+        delay: &mut impl DelayNs,
+        config: &Config,
+    ) -> Result<FirmwareInfo, Error<I2C::Error>> {
+        self.reset_with(ResetTimings::default(), delay)?;
+        match self.wait_until_ready(delay, READY_TIMEOUT_MS) {
+            Ok(()) => {}
+            Err(Error::Timeout) => {
+                // Report why the chip was not accepted instead of the timeout
+                self.probe()?;
+                return Err(Error::Timeout);
+            }
+            Err(e) => return Err(e),
+        }
+        let info = self.firmware_info()?;
+        self.set_variant(info.variant);
+        self.apply(config)?;
+        Ok(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ChipVariant, DEFAULT_ADDRESS, KNOWN_CHIP_IDS, testing};
+    use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+    use embedded_hal_mock::eh1::{
+        delay::NoopDelay,
+        digital::{self, State},
+        i2c,
+    };
+    use futures_test::test;
+
+    fn read(register: u8, value: u8) -> i2c::Transaction {
+        i2c::Transaction::write_read(testing::ADDRESS, vec![register], vec![value])
+    }
+
+    fn reset_pin() -> digital::Mock {
+        digital::Mock::new(&[
+            digital::Transaction::set(State::High),
+            digital::Transaction::set(State::Low),
+            digital::Transaction::set(State::High),
+        ])
+    }
+
+    #[test]
+    async fn init_reads_info_and_configures() {
+        let mut expectations = vec![
+            read(0xA7, 0xB5),
+            read(0xA7, 0xB5),
+            read(0xA8, 0x00),
+            read(0xA9, 0x02),
+        ];
+        expectations.extend(testing::write(0xFA, &[0b0111_0001]));
+        expectations.extend(testing::write(0xFE, &[0xFE]));
+        expectations.extend(testing::write(0xED, &[0x01]));
+        expectations.extend(testing::write(0xEE, &[0x01]));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = reset_pin();
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        let info = touchpad.init(&mut NoopDelay::new()).unwrap();
+        assert_eq!(
+            info,
+            FirmwareInfo {
+                variant: ChipVariant::Cst816T,
+                project_id: 0x00,
+                fw_version: 0x02,
+            }
+        );
+        assert_eq!(touchpad.variant(), ChipVariant::Cst816T);
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn init_rejects_wrong_chip_id() {
+        let polls = READY_TIMEOUT_MS.div_ceil(crate::DEFAULT_POLL_INTERVAL_MS) as usize + 1;
+        let mut i2c_device = i2c::Mock::new(&vec![read(0xA7, 0x42); polls + 1]);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = reset_pin();
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        assert_eq!(
+            touchpad.init(&mut NoopDelay::new()),
+            Err(Error::WrongChipId {
+                expected: &KNOWN_CHIP_IDS,
+                found: 0x42
+            })
+        );
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn init_reports_missing_chip_as_bus_error() {
+        let nack = ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address);
+        let polls = READY_TIMEOUT_MS.div_ceil(crate::DEFAULT_POLL_INTERVAL_MS) as usize + 1;
+        let mut i2c_device = i2c::Mock::new(&vec![read(0xA7, 0x00).with_error(nack); polls + 1]);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = reset_pin();
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        assert_eq!(touchpad.init(&mut NoopDelay::new()), Err(Error::I2c(nack)));
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+}
@@ -2,148 +2,1249 @@
 //!
 //! Device Driver Crate for CST816S
 //!
+//! # Optional pins
+//!
+//! Boards that do not route the reset line to a GPIO, for example because it is tied to the
+//! system reset or a power rail, use [`CST816S::new_no_reset`]. The driver then holds a
+//! [`NoResetPin`] and [`CST816S::reset`] fails with [`Error::Pin`] instead of toggling a pin;
+//! see [`NoResetPin`] for telling this apart from a faulty pin.
+//!
+//! Boards without the interrupt line use [`CST816S::new_polling`], see [`NoIntPin`].
+//!
+//! # Sharing the I2C bus
+//!
+//! The driver only needs an [`I2c`] implementation, so on boards where the touch controller
+//! shares the bus with other peripherals, hand it one of the shared bus devices from
+//! [`embedded-hal-bus`](https://crates.io/crates/embedded-hal-bus), for example
+//! `RefCellDevice` on a single core or `CriticalSectionDevice` and `AtomicDevice` when the
+//! bus is used from interrupts. A `&mut` reference to a bus also works for short-lived drivers.
+//! [`CST816S::release`] gives the bus handle back.
+//!
+//! # Cargo features
+//!
+//! All features are off by default, so the core I2C driver only depends on `embedded-hal` and
+//! `device-driver`.
+//!
+//! - `defmt-03`: `defmt::Format` for the public types
+//! - `float`: normalized `f32` coordinates, see `TouchPoint::normalized`
+//! - `embedded-graphics`: conversions from [`TouchPoint`] and [`TouchEvent`] into
+//!   `embedded_graphics_core::geometry::Point`, for hit-testing against drawn shapes. The
+//!   integer [`regions`] work without it
+//! - `multi-touch`: [`CST816S::event_multi`] for firmware reporting a second contact
+//! - `serde`: `Serialize` and `Deserialize` for [`Config`] and the types it holds, to store
+//!   a configuration captured with [`CST816S::read_config`]
+//! - `test-util`: the `mock` module with an in-memory register bank for testing code that uses
+//!   the driver
+//!
 //! # Examples
 #![cfg_attr(not(test), no_std)]
 #![warn(missing_docs)]
 
 use embedded_hal::{
     delay::DelayNs,
-    digital::{InputPin, OutputPin},
-    i2c::{I2c, SevenBitAddress},
+    digital::{OutputPin, PinState},
+    i2c::I2c,
 };
 
 pub mod device;
-use device::{Device, DeviceError, DeviceInterface, PulseWidth};
+use device::{Device, DeviceError, DeviceInterface, field_sets::TouchData};
+
+mod address;
+pub use address::{Address, DEFAULT_ADDRESS, KNOWN_ADDRESSES};
+
+mod config;
+pub use config::{
+    CONFIG_BLOCK_LEN, CONFIG_DIFF_LEN, Config, ConfigDiff, IoVoltage, IrqConfig, IrqSources,
+    LowPowerConfig, MotionMaskConfig, RegisterMismatch,
+};
+
+mod diagnostics;
+pub use diagnostics::Baseline;
+
+mod error;
+pub use error::Error;
+
+mod events;
+pub use events::Events;
+
+mod geometry;
+pub use geometry::{BoundsPolicy, CoordinateWidth, Orientation};
+
+mod point;
+#[cfg(feature = "float")]
+pub use point::NormalizedPoint;
+pub use point::{TouchPoint, combine_coord};
+
+mod init;
+pub use init::READY_TIMEOUT_MS;
+
+#[cfg(any(test, feature = "test-util"))]
+pub mod mock;
+
+#[cfg(feature = "multi-touch")]
+mod multi;
+
+mod polling;
+pub use polling::{NoIntPin, TouchReady};
+
+pub mod regions;
+
+mod queue;
+pub use queue::BufferedCST816S;
+
+mod reset;
+pub use reset::{MissingResetPin, NoResetPin, ResetPolarity, ResetTimings};
+
+mod split;
+pub use split::{ConfigHandle, Detached, TouchReader};
+
+mod state;
+pub use state::{TouchPhase, TouchState};
+
+mod values;
+pub use values::{
+    LpScanCurrent, LpScanFrequency, LpScanThreshold, LpScanWindow, PulseWidth, ScanPeriod,
+    SleepSeconds, ValueOutOfRange, WakeMinutes,
+};
+
+mod transport;
+pub use transport::{RegisterBus, RegisterBusError};
+
+mod variant;
+pub use variant::{ChipVariant, FirmwareInfo, KNOWN_CHIP_IDS};
+
+mod wait;
+pub use wait::DEFAULT_POLL_INTERVAL_MS;
+
+#[cfg(test)]
+mod testing;
+
+/// Default number of reads [`CST816S::events`] performs before giving up on a stuck interrupt line.
+pub const DEFAULT_MAX_EVENTS: usize = 8;
 
 /// Public interface struct for our High-level driver
 pub struct CST816S<I2C, TPINT, TPRST> {
     device: Device<DeviceInterface<I2C>>,
     interrupt_pin: TPINT,
     reset_pin: TPRST,
+    /// Where the finger was on the last [`CST816S::touch_state`] or [`CST816S::poll`]
+    held: Option<TouchPoint>,
+    reset_polarity: ResetPolarity,
+    variant: ChipVariant,
+    /// Whether reports include the `BPC0`/`BPC1` values, see [`CST816S::set_read_bpc`]
+    bpc_in_reports: bool,
+    last_event: Option<TouchEvent>,
+    /// Sequence number of the next report, advanced by [`CST816S::acknowledge`]
+    sequence: u32,
+    /// Whether reports at (0, 0) without a finger are dropped, see [`CST816S::set_reject_origin_glitch`]
+    reject_origin_glitch: bool,
+    /// Whether a failed interrupt pin read counts as pending, see
+    /// [`CST816S::set_assume_ready_on_pin_error`]
+    assume_ready_on_pin_error: bool,
+    /// Whether reports reapply the configuration, see [`CST816S::set_auto_reapply`]
+    auto_reapply: bool,
+    /// Set by a reset, the configuration is written again before the next report
+    reapply_pending: bool,
+    panel_size: Option<(u16, u16)>,
+    bounds_policy: BoundsPolicy,
+    orientation: Orientation,
+    coordinate_width: CoordinateWidth,
+    /// Whether slide gestures follow the orientation, see [`CST816S::set_rotate_gestures`]
+    rotate_gestures: bool,
+    move_threshold: u16,
+    debounce: u16,
+    /// Last point reported while debouncing, see [`CST816S::set_debounce`]
+    stable_point: Option<TouchPoint>,
+    /// Last reported point, see [`CST816S::last_touch_region`]
+    last_point: Option<TouchPoint>,
+    /// Point of the previous slide report, see [`CST816S::scroll_delta`]
+    scroll_point: Option<TouchPoint>,
+    /// Last configuration written, see [`CST816S::reapply_config`]
+    applied_config: Option<Config>,
+    /// Interrupt configuration to restore, see [`CST816S::set_interrupts_enabled`]
+    muted_irq: Option<IrqConfig>,
 }
 
-impl<I2C, TPINT, TPRST> CST816S<I2C, TPINT, TPRST>
-where
-    I2C: I2c,
-    TPINT: InputPin,
-    TPRST: OutputPin,
-{
+impl<I2C, TPINT, TPRST> CST816S<I2C, TPINT, TPRST> {
     /// make a new instance, yeah!
     ///
+    /// `address` is usually [`DEFAULT_ADDRESS`], see [`CST816S::scan`] for modules strapped to
+    /// another one, and [`Address::try_new`] for others.
+    ///
     /// ```compile_fail
     ///     let driver = CST816S::new(...);
     /// ```
-    pub fn new(i2c: I2C, address: SevenBitAddress, interrupt_pin: TPINT, reset_pin: TPRST) -> Self {
+    pub fn new(i2c: I2C, address: Address, interrupt_pin: TPINT, reset_pin: TPRST) -> Self {
         Self {
-            device: Device::new(DeviceInterface::new(i2c, address)),
+            device: Device::new(DeviceInterface::new(i2c, address.get())),
             interrupt_pin,
             reset_pin,
+            held: None,
+            reset_polarity: ResetPolarity::ActiveLow,
+            variant: ChipVariant::Cst816S,
+            bpc_in_reports: false,
+            last_event: None,
+            sequence: 0,
+            reject_origin_glitch: false,
+            assume_ready_on_pin_error: false,
+            auto_reapply: false,
+            reapply_pending: false,
+            panel_size: None,
+            bounds_policy: BoundsPolicy::PassThrough,
+            orientation: Orientation::Deg0,
+            coordinate_width: CoordinateWidth::Bits12,
+            rotate_gestures: false,
+            move_threshold: 0,
+            debounce: 0,
+            stable_point: None,
+            last_point: None,
+            scroll_point: None,
+            applied_config: None,
+            muted_irq: None,
         }
     }
 
+    /// Destroy the driver and return the I2C bus and the pins.
+    ///
+    /// Useful on shared buses, or to reconfigure the pins for something else.
+    pub fn release(self) -> (I2C, TPINT, TPRST) {
+        (
+            self.device.interface.release(),
+            self.interrupt_pin,
+            self.reset_pin,
+        )
+    }
+
+    /// The most recently decoded report, if any.
+    ///
+    /// Every read that decodes a [`TouchEvent`], like [`CST816S::event`] or
+    /// [`BufferedCST816S::pump`], updates it, so a render loop can draw the last touch even
+    /// when no new report arrived this frame.
+    pub fn last_event(&self) -> Option<&TouchEvent> {
+        self.last_event.as_ref()
+    }
+
+    /// Take the most recently decoded report, leaving `None` until the next one.
+    pub fn take_last_event(&mut self) -> Option<TouchEvent> {
+        self.last_event.take()
+    }
+}
+
+/// Reset and configuration.
+///
+/// These live with the reset pin, so after [`CST816S::split`] only the [`ConfigHandle`] has them.
+impl<I2C, TPINT, TPRST> CST816S<I2C, TPINT, TPRST>
+where
+    I2C: I2c,
+    TPRST: OutputPin,
+{
+    /// Set which level of the reset pin holds the chip in reset.
+    ///
+    /// Defaults to [`ResetPolarity::ActiveLow`]. Every reset sequence of the driver follows it.
+    pub fn set_reset_polarity(&mut self, polarity: ResetPolarity) {
+        self.reset_polarity = polarity;
+    }
+
     /// Reset the device
     ///
-    /// Make sure the device is in "dynamic mode" by pulling the reset pin low for 20ms, then setting it high again.
-    pub fn reset(&mut self, delay: &mut impl DelayNs) -> Result<(), TPRST::Error> {
-        self.reset_pin.set_high()?;
-        delay.delay_ms(50);
-        self.reset_pin.set_low()?;
-        delay.delay_ms(5);
-        self.reset_pin.set_high()?;
-        delay.delay_ms(50);
+    /// Make sure the device is in "dynamic mode" by pulling the reset pin low for 5ms, then setting it high again.
+    /// With [`ResetPolarity::ActiveHigh`] the levels are inverted.
+    /// Uses the default [`ResetTimings`], see [`CST816S::reset_with`] to adjust them.
+    ///
+    /// Fails with [`Error::Pin`] if the reset pin cannot be driven, so it shares the error type
+    /// with the configuration methods.
+    pub fn reset(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<I2C::Error>> {
+        self.reset_with(ResetTimings::default(), delay)
+    }
+
+    /// Reset the device using custom timings.
+    ///
+    /// Some panels need a longer low pulse or start-up time than the defaults.
+    pub fn reset_with(
+        &mut self,
+        timings: ResetTimings,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Error<I2C::Error>> {
+        let polarity = self.reset_polarity;
+        self.set_reset_pin(polarity.level(false))?;
+        delay.delay_ms(timings.pre_high_ms);
+        self.set_reset_pin(polarity.level(true))?;
+        delay.delay_ms(timings.low_ms);
+        self.set_reset_pin(polarity.level(false))?;
+        delay.delay_ms(timings.post_high_ms);
+        self.reapply_pending = true;
         Ok(())
     }
 
-    /// Set initial default config
-    pub fn init_config(&mut self) -> Result<(), DeviceError<I2C::Error>> {
-        self.device.irq_ctl().write(|irq_ctl| {
-            irq_ctl.set_en_test(false);
-            irq_ctl.set_en_touch(true);
-            irq_ctl.set_once_wlp(true);
-            irq_ctl.set_en_change(true);
-            irq_ctl.set_en_motion(true);
-        })?;
-        self.device.motion_mask().write(|mask| {
-            mask.set_en_d_click(true);
-            mask.set_en_con_lr(true);
-            mask.set_en_con_ud(true);
-        })?;
-        // self.device.motion_sl_angle().write(|m| m.set_value(0))?;
-        // self.device.lp_scan_th().write(|m| m.set_value(48))?;
-        // self.device.lp_scan_win().write(|m| m.set_value(3))?;
-        // self.device.lp_scan_freq().write(|m| m.set_value(7))?;
-        // self.device.lp_scan_idac().write(|m| m.set_value(1))?;
-        // self.device.auto_reset().write(|m| m.set_value(5))?;
-        self.device.dis_auto_sleep().write(|m| m.set_value(0xfe))?;
-        self.device
-            .irq_pulse_width()
-            .write(|m| m.set_value(PulseWidth::new(1)))?;
-        self.device.nor_scan_per().write(|m| m.set_value(1))?;
-        return Ok(());
+    /// Reset the device using an async delay.
+    ///
+    /// Same sequence as [`CST816S::reset_with`], but yields to the executor while waiting
+    /// instead of blocking, which suits embassy and other async runtimes.
+    pub async fn reset_async(
+        &mut self,
+        timings: ResetTimings,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+    ) -> Result<(), Error<I2C::Error>> {
+        let polarity = self.reset_polarity;
+        self.set_reset_pin(polarity.level(false))?;
+        delay.delay_ms(timings.pre_high_ms).await;
+        self.set_reset_pin(polarity.level(true))?;
+        delay.delay_ms(timings.low_ms).await;
+        self.set_reset_pin(polarity.level(false))?;
+        delay.delay_ms(timings.post_high_ms).await;
+        self.reapply_pending = true;
+        Ok(())
     }
 
-    /// Read the ChipId register if the device is available for reads
-    pub fn read_chip_id(&mut self) -> Option<u8> {
-        let int_pin_value = self.interrupt_pin.is_low().unwrap();
-        if int_pin_value {
-            let result = self.device.chip_id().read().unwrap().value();
-            Some(result)
-        } else {
-            None
-        }
+    fn set_reset_pin(&mut self, state: PinState) -> Result<(), Error<I2C::Error>> {
+        self.reset_pin.set_state(state).map_err(Error::pin)
+    }
+
+    /// Set initial default config
+    ///
+    /// Same as [`CST816S::apply`] with [`Config::default`]. The `MotionMask` register is only
+    /// written if the [`ChipVariant`] has it. The registers are written without checking what is
+    /// on the bus, call [`CST816S::probe`] first to catch a mis-wired bus at boot.
+    pub fn init_config(&mut self) -> Result<(), DeviceError<I2C::Error>> {
+        self.write_config(&Config::default())
     }
 
     /// Set the IrqPulseWidth register.
     ///
     /// Allows you to set the time the interrupt pin is low.
     /// unit is 0.1ms and the range is 1-200. Default is 10
-    pub fn set_irq_pulse_width(&mut self, pulse_width: PulseWidth) {
+    pub fn set_irq_pulse_width(
+        &mut self,
+        pulse_width: PulseWidth,
+    ) -> Result<(), DeviceError<I2C::Error>> {
         self.device
             .irq_pulse_width()
-            .write(|write_object| write_object.set_value(pulse_width))
-            .unwrap();
+            .write(|write_object| write_object.set_value(pulse_width))?;
+        self.update_applied_config(|applied| applied.irq_pulse_width = pulse_width);
+        Ok(())
+    }
+
+    /// Set the interrupt pulse width in microseconds, rounded down to the 0.1ms steps of the
+    /// register, so 1000µs writes 10.
+    ///
+    /// Returns [`Error::OutOfRange`] outside of 100..=20000µs, see [`PulseWidth::from_us`].
+    pub fn set_irq_pulse_width_us(&mut self, micros: u16) -> Result<(), Error<I2C::Error>> {
+        self.set_irq_pulse_width(PulseWidth::from_us(micros)?)?;
+        Ok(())
+    }
+
+    /// Read the IrqPulseWidth register.
+    ///
+    /// Returns [`Error::OutOfRange`] if the register holds a value outside of 1-200, as some
+    /// clones report 0.
+    pub fn irq_pulse_width(&mut self) -> Result<PulseWidth, Error<I2C::Error>> {
+        Ok(self.device.irq_pulse_width().read()?.value()?)
+    }
+}
+
+/// Reading touch reports.
+///
+/// These live with the interrupt pin, so after [`CST816S::split`] only the [`TouchReader`] has them.
+impl<I2C, TPINT, TPRST> CST816S<I2C, TPINT, TPRST>
+where
+    I2C: I2c,
+    TPINT: TouchReady,
+{
+    /// Read the ChipId register if the device is available for reads
+    ///
+    /// Returns `Ok(None)` while the interrupt pin signals no pending report, as the chip may be
+    /// asleep then. [`CST816S::read_chip_id_now`] skips that check. Pin and bus faults are
+    /// returned as errors.
+    pub fn read_chip_id(&mut self) -> Result<Option<u8>, Error<I2C::Error>> {
+        if !self.interrupt_pending()? {
+            return Ok(None);
+        }
+        self.read_chip_id_now().map(Some)
     }
 
     /// Read a single event.
     ///
     /// Will return a [`TouchEvent`] struct if the device has a valid touch ready.
+    /// This is [`CST816S::peek_event`] followed by [`CST816S::acknowledge`]; errors are
+    /// dropped, use [`CST816S::read_into`] to see them.
     pub fn event(&mut self) -> Option<TouchEvent> {
-        if self.interrupt_pin.is_high().unwrap() {
-            return None;
+        let mut event = TouchEvent::default();
+        self.read_into(&mut event).ok()?.then_some(event)
+    }
+
+    /// Read a single event into `out`, returning whether a touch was read.
+    ///
+    /// Like [`CST816S::event`], but fills a caller owned [`TouchEvent`] and returns bus and pin
+    /// errors instead of dropping them. `out` is only valid when `Ok(true)` is returned, it is
+    /// left untouched otherwise. The report is acknowledged.
+    pub fn read_into(&mut self, out: &mut TouchEvent) -> Result<bool, Error<I2C::Error>> {
+        if !self.interrupt_pending()? {
+            return Ok(false);
         }
-        let x = self.device.xpos().read();
-        let y = self.device.ypos().read();
-        let b0 = self.device.bpc_0().read();
-        let b1 = self.device.bpc_1().read();
-        let gesture = self.device.gesture_id().read();
-        if x.is_err() || y.is_err() || gesture.is_err() || b0.is_err() || b1.is_err() {
+        let Some(event) = self.read_report()? else {
+            return Ok(false);
+        };
+        *out = event;
+        self.acknowledge();
+        Ok(true)
+    }
+
+    /// Read the pending report without acknowledging it.
+    ///
+    /// Calling this repeatedly returns the same report until the chip produces a new one,
+    /// which lets one part of an application check for a pending touch while another part
+    /// processes it with [`CST816S::event`]. Like that one, errors are dropped.
+    ///
+    /// The driver state is left as it is: [`CST816S::last_event`] and the debounce point are
+    /// not updated, and a configuration due for [`CST816S::set_auto_reapply`] is not written.
+    pub fn peek_event(&mut self) -> Option<TouchEvent> {
+        if !self.interrupt_pending().ok()? {
             return None;
         }
-        let x = x.unwrap().value();
-        let y = y.unwrap().value();
-        let bpc0 = b0.unwrap().value();
-        let bpc1 = b1.unwrap().value();
-        let gesture = gesture.unwrap().value().unwrap();
-        let point: Point = (x, y);
-
-        Some(TouchEvent {
+        let data = self.read_touch_data().ok()?;
+        self.decode(data).ok().flatten()
+    }
+
+    /// Drain all pending reports.
+    ///
+    /// Keeps reading reports for as long as the interrupt pin stays low, reading at most
+    /// [`DEFAULT_MAX_EVENTS`] times. Use [`CST816S::events_with_limit`] to pick another bound.
+    pub fn events(&mut self) -> Events<'_, I2C, TPINT, TPRST> {
+        self.events_with_limit(DEFAULT_MAX_EVENTS)
+    }
+
+    /// Drain all pending reports, reading at most `max_reads` times.
+    pub fn events_with_limit(&mut self, max_reads: usize) -> Events<'_, I2C, TPINT, TPRST> {
+        Events::new(self, max_reads)
+    }
+
+    /// Treat a failed read of the interrupt pin as a pending report.
+    ///
+    /// By default a pin read error is returned as [`Error::Pin`], or makes [`CST816S::event`]
+    /// return `None`. Some HALs fail pin reads for a moment while a shared GPIO bank is
+    /// reconfigured; with this enabled the driver reads the touch registers instead, which
+    /// report no touch if there is none. Off by default.
+    pub fn set_assume_ready_on_pin_error(&mut self, enabled: bool) {
+        self.assume_ready_on_pin_error = enabled;
+    }
+
+    /// Check whether the interrupt pin, or `FingerNum` for [`NoIntPin`], signals a pending report.
+    pub(crate) fn interrupt_pending(&mut self) -> Result<bool, Error<I2C::Error>> {
+        match TPINT::touch_ready(self) {
+            Err(Error::Pin(_)) if self.assume_ready_on_pin_error => Ok(true),
+            result => result,
+        }
+    }
+}
+
+/// Reading the touch registers without looking at the interrupt pin.
+impl<I2C, TPINT, TPRST> CST816S<I2C, TPINT, TPRST>
+where
+    I2C: I2c,
+{
+    /// Read the touch report registers (0x01 to 0x06) in a single transaction.
+    ///
+    /// The returned field set holds the gesture, finger count and coordinates.
+    pub fn read_touch_data(&mut self) -> Result<TouchData, DeviceError<I2C::Error>> {
+        self.device.touch_data().read()
+    }
+
+    /// Include the `BPC0` and `BPC1` values in every [`TouchEvent`].
+    ///
+    /// Off by default, so a report is a single burst read of 0x01 to 0x06. Turning it on adds two
+    /// reads per report; [`CST816S::read_bpc`] reads the values once instead.
+    pub fn set_read_bpc(&mut self, enabled: bool) {
+        self.bpc_in_reports = enabled;
+    }
+
+    /// Drop reports at (0, 0) that come without a finger on the panel.
+    ///
+    /// Some panels send such a report, often with a [`device::Gesture::SingleClick`], right
+    /// after waking up, which shows up as a phantom tap in the corner. A real touch at (0, 0)
+    /// has `FingerNum` set and is still reported. Off by default; applies to every way of
+    /// reading reports, including [`CST816S::events`] and [`BufferedCST816S`].
+    pub fn set_reject_origin_glitch(&mut self, enabled: bool) {
+        self.reject_origin_glitch = enabled;
+    }
+
+    /// Mark the current report as handled.
+    ///
+    /// The CST816S drives the interrupt pin as a fixed-width low pulse (see
+    /// [`CST816S::set_irq_pulse_width`]), so no register read is needed for the line to
+    /// return high. Acknowledging only updates the driver's own bookkeeping: the next report
+    /// gets the next [`TouchEvent::sequence`] number.
+    pub fn acknowledge(&mut self) {
+        self.sequence = self.sequence.wrapping_add(1);
+    }
+
+    /// Read a report by polling the registers, for boards without the interrupt line.
+    ///
+    /// Returns `Ok(None)` when no finger is on the panel and no gesture is reported, otherwise
+    /// the decoded [`TouchEvent`]. The report is not acknowledged.
+    ///
+    /// The chip only answers on the bus while it is awake. With auto-sleep enabled it stops
+    /// responding a few seconds after the last touch and every read fails with a NACK, so
+    /// disable auto-sleep through the `DisAutoSleep` register when polling. This keeps the chip
+    /// in its normal scanning mode and costs noticeably more power than interrupt driven reads.
+    pub fn read_report_unchecked(&mut self) -> Result<Option<TouchEvent>, Error<I2C::Error>> {
+        let data = self.read_touch_data()?;
+        if data.finger_num() == 0 && data.gesture() == device::Gesture::NoGesture {
+            return Ok(None);
+        }
+        self.decode_report(data)
+    }
+
+    /// Read the finger currently on the panel, ignoring the interrupt pin.
+    ///
+    /// Returns `Ok(None)` when `FingerNum` is zero, even if the registers still hold a gesture,
+    /// so unlike [`CST816S::read_report_unchecked`] this only reports a finger that is down right
+    /// now. Meant for diagnostics and boards with a flaky or missing interrupt line; the same
+    /// auto-sleep caveat applies. The report is not acknowledged.
+    pub fn read_touch_now(&mut self) -> Result<Option<TouchEvent>, Error<I2C::Error>> {
+        let data = self.read_touch_data()?;
+        if data.finger_num() == 0 {
+            return Ok(None);
+        }
+        self.decode_report(data)
+    }
+
+    /// Read the coordinates and the event flag of the `XposH` register in one transaction.
+    ///
+    /// The point is in panel coordinates as reported by the chip, before the
+    /// [`Orientation`], bounds and debounce handling of [`CST816S::event`]. A lift reports
+    /// [`TouchEventType::Up`](device::TouchEventType::Up) at the last point, even though
+    /// `FingerNum` is already zero. Returns `Ok(None)` when the flag is
+    /// [`TouchEventType::NoEvent`](device::TouchEventType::NoEvent).
+    pub fn read_point_with_flags(
+        &mut self,
+    ) -> Result<Option<(TouchPoint, device::TouchEventType)>, Error<I2C::Error>> {
+        let data = self.read_touch_data()?;
+        Ok(match data.event() {
+            device::TouchEventType::NoEvent => None,
+            event => Some((self.raw_point(data.x(), data.y()), event)),
+        })
+    }
+
+    /// Read the touch registers and decode them into a [`TouchEvent`].
+    ///
+    /// Returns `Ok(None)` if the report was dropped, see [`CST816S::decode_report`].
+    pub(crate) fn read_report(&mut self) -> Result<Option<TouchEvent>, Error<I2C::Error>> {
+        let data = self.read_touch_data()?;
+        self.decode_report(data)
+    }
+
+    fn is_origin_glitch(&self, data: &TouchData) -> bool {
+        self.reject_origin_glitch
+            && data.finger_num() == 0
+            && self.raw_point(data.x(), data.y()) == TouchPoint::new(0, 0)
+    }
+
+    /// Decode a report and record it as the last event, returning `Ok(None)` if it is dropped
+    /// by [`CST816S::set_reject_origin_glitch`] or [`BoundsPolicy::Reject`].
+    fn decode_report(&mut self, data: TouchData) -> Result<Option<TouchEvent>, Error<I2C::Error>> {
+        self.auto_reapply_config()?;
+        self.decode_and_record(data)
+    }
+
+    /// Read and decode a report like [`CST816S::read_report`], but never write the configuration
+    /// for [`CST816S::set_auto_reapply`], so the number of bus transfers stays fixed.
+    pub(crate) fn read_report_without_reapply(
+        &mut self,
+    ) -> Result<Option<TouchEvent>, Error<I2C::Error>> {
+        let data = self.read_touch_data()?;
+        self.decode_and_record(data)
+    }
+
+    fn decode_and_record(
+        &mut self,
+        data: TouchData,
+    ) -> Result<Option<TouchEvent>, Error<I2C::Error>> {
+        let lifted = data.finger_num() == 0 || data.event() == device::TouchEventType::Up;
+        let event = self.decode(data)?;
+        if let Some(event) = event {
+            self.record_event(event);
+        }
+        if lifted {
+            // The next touch starts fresh instead of snapping to where this one ended
+            self.stable_point = None;
+        }
+        Ok(event)
+    }
+
+    /// Decode a report without changing the driver state, see [`CST816S::decode_report`].
+    fn decode(&mut self, data: TouchData) -> Result<Option<TouchEvent>, Error<I2C::Error>> {
+        if self.is_origin_glitch(&data) {
+            return Ok(None);
+        }
+        let Some(point) = self.to_display(self.raw_point(data.x(), data.y())) else {
+            return Ok(None);
+        };
+        let point = self.debounced(point);
+        let (bpc0, bpc1) = if self.bpc_in_reports {
+            let (bpc0, bpc1) = self.read_bpc()?;
+            (Some(bpc0), Some(bpc1))
+        } else {
+            (None, None)
+        };
+        let gesture = self.to_display_gesture(data.gesture());
+        let event_type = data.event();
+
+        let event = TouchEvent {
             point,
             bpc0,
             bpc1,
             gesture,
-        })
+            event_type,
+            sequence: self.sequence,
+        };
+        Ok(Some(event))
+    }
+
+    fn record_event(&mut self, event: TouchEvent) {
+        self.hold_point(event.point);
+        self.last_event = Some(event);
+        self.last_point = Some(event.point);
     }
 }
 
 /// Named type `Point`. represent the point a touch was registered at.
+#[deprecated(note = "use `TouchPoint`, which converts from and into this tuple")]
 pub type Point = (u16, u16);
 
 /// `TouchEvent` struct contains the point and gesture of a received touch event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
 pub struct TouchEvent {
     /// Where on the screen was the touch registered.
-    pub point: Point,
-    pub bpc0: u16,
-    pub bpc1: u16,
+    pub point: TouchPoint,
+    /// `BPC0` value, only read when enabled with [`CST816S::set_read_bpc`]
+    pub bpc0: Option<u16>,
+    /// `BPC1` value, only read when enabled with [`CST816S::set_read_bpc`]
+    pub bpc1: Option<u16>,
     /// What type of gesture was registered,
     pub gesture: device::Gesture,
+    /// Event flag from the top bits of `XposH`, telling a new press from a sustained contact
+    pub event_type: device::TouchEventType,
+    /// Number of the hardware report this event was decoded from.
+    ///
+    /// Advances by one, wrapping, every time a report is acknowledged, so reading the same
+    /// report twice (for example with [`CST816S::peek_event`]) gives the same number. Use it to
+    /// drop duplicates after passing events through a queue or mailbox.
+    pub sequence: u32,
+}
+
+/// An empty event at (0, 0), for use with [`CST816S::read_into`].
+impl Default for TouchEvent {
+    fn default() -> Self {
+        Self {
+            point: TouchPoint::default(),
+            bpc0: None,
+            bpc1: None,
+            gesture: device::Gesture::NoGesture,
+            event_type: device::TouchEventType::NoEvent,
+            sequence: 0,
+        }
+    }
+}
+
+impl core::fmt::Display for TouchEvent {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} at {}", self.gesture, self.point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_hal::digital::InputPin;
+    use embedded_hal_mock::eh1::{
+        delay::{CheckedDelay, Transaction as DelayTransaction},
+        digital::{self, State},
+        i2c,
+    };
+    use futures_test::test;
+
+    use crate::testing;
+
+    #[test]
+    async fn reset_active_high_inverts_levels() {
+        let mut i2c_device = i2c::Mock::new(&[]);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[
+            digital::Transaction::set(State::Low),
+            digital::Transaction::set(State::High),
+            digital::Transaction::set(State::Low),
+        ]);
+        let mut delay = CheckedDelay::new(&[
+            DelayTransaction::blocking_delay_ms(50),
+            DelayTransaction::blocking_delay_ms(5),
+            DelayTransaction::blocking_delay_ms(50),
+        ]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        touchpad.set_reset_polarity(ResetPolarity::ActiveHigh);
+        touchpad.reset(&mut delay).unwrap();
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+        delay.done();
+    }
+
+    #[test]
+    async fn reset_with_custom_timings() {
+        let mut i2c_device = i2c::Mock::new(&[]);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[
+            digital::Transaction::set(State::High),
+            digital::Transaction::set(State::Low),
+            digital::Transaction::set(State::High),
+        ]);
+        let mut delay = CheckedDelay::new(&[
+            DelayTransaction::blocking_delay_ms(100),
+            DelayTransaction::blocking_delay_ms(10),
+            DelayTransaction::blocking_delay_ms(200),
+        ]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        let timings = ResetTimings {
+            pre_high_ms: 100,
+            low_ms: 10,
+            post_high_ms: 200,
+        };
+        touchpad.reset_with(timings, &mut delay).unwrap();
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+        delay.done();
+    }
+
+    #[test]
+    async fn reset_async_uses_default_timings() {
+        let mut i2c_device = i2c::Mock::new(&[]);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[
+            digital::Transaction::set(State::High),
+            digital::Transaction::set(State::Low),
+            digital::Transaction::set(State::High),
+        ]);
+        let mut delay = CheckedDelay::new(&[
+            DelayTransaction::async_delay_ms(50),
+            DelayTransaction::async_delay_ms(5),
+            DelayTransaction::async_delay_ms(50),
+        ]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        touchpad
+            .reset_async(ResetTimings::default(), &mut delay)
+            .await
+            .unwrap();
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+        delay.done();
+    }
+
+    #[test]
+    async fn event_with_unknown_gesture() {
+        let mut i2c_device = i2c::Mock::new(&testing::report(0x07, 0x10, 0x20));
+        let mut int_pin = digital::Mock::new(&[digital::Transaction::get(State::Low)]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        let event = touchpad.event().unwrap();
+
+        assert_eq!(event.point, TouchPoint::new(0x10, 0x20));
+        assert_eq!(event.gesture, device::Gesture::Unknown(0x07));
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn events_drains_while_interrupt_low() {
+        let report = testing::report(0x00, 0x10, 0x20);
+        let mut i2c_device = i2c::Mock::new(report.iter().cycle().take(report.len() * 3));
+        let mut int_pin = digital::Mock::new(&[
+            digital::Transaction::get(State::Low),
+            digital::Transaction::get(State::Low),
+            digital::Transaction::get(State::Low),
+            digital::Transaction::get(State::High),
+        ]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        let events: Vec<_> = touchpad.events().collect();
+        assert_eq!(events.len(), 3);
+        assert!(events.iter().all(Result::is_ok));
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn events_stops_at_limit() {
+        let report = testing::report(0x00, 0x10, 0x20);
+        let mut i2c_device = i2c::Mock::new(report.iter().cycle().take(report.len() * 2));
+        let mut int_pin = digital::Mock::new(&[
+            digital::Transaction::get(State::Low),
+            digital::Transaction::get(State::Low),
+        ]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        let count = touchpad.events_with_limit(2).count();
+        assert_eq!(count, 2);
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn event_reads_bpc_only_when_enabled() {
+        let mut expectations = testing::report(0x05, 0x10, 0x20);
+        expectations.extend(testing::report(0x05, 0x10, 0x20));
+        expectations.push(i2c::Transaction::write_read(
+            testing::ADDRESS,
+            vec![0xB0],
+            vec![0x12, 0x34],
+        ));
+        expectations.push(i2c::Transaction::write_read(
+            testing::ADDRESS,
+            vec![0xB2],
+            vec![0x56, 0x78],
+        ));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[
+            digital::Transaction::get(State::Low),
+            digital::Transaction::get(State::Low),
+        ]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        let lean = touchpad.event().unwrap();
+        assert_eq!((lean.bpc0, lean.bpc1), (None, None));
+        touchpad.set_read_bpc(true);
+        let full = touchpad.event().unwrap();
+        assert_eq!((full.bpc0, full.bpc1), (Some(0x1234), Some(0x5678)));
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn peek_event_is_repeatable() {
+        let report = testing::report(0x05, 0x10, 0x20);
+        let mut i2c_device = i2c::Mock::new(report.iter().chain(report.iter()));
+        let mut int_pin = digital::Mock::new(&[
+            digital::Transaction::get(State::Low),
+            digital::Transaction::get(State::Low),
+        ]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        touchpad.set_debounce(4);
+        let first = touchpad.peek_event().unwrap();
+        let second = touchpad.peek_event().unwrap();
+        assert!(touchpad.last_event().is_none());
+        assert!(touchpad.stable_point.is_none());
+
+        assert_eq!(first.point, second.point);
+        assert_eq!(first.bpc0, second.bpc0);
+        assert_eq!(first.bpc1, second.bpc1);
+        assert_eq!(first.gesture, second.gesture);
+        assert_eq!(second.gesture, device::Gesture::SingleClick);
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn read_report_unchecked_without_touch() {
+        let mut i2c_device = i2c::Mock::new(&[i2c::Transaction::write_read(
+            testing::ADDRESS,
+            vec![0x01],
+            vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        )]);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        assert!(touchpad.read_report_unchecked().unwrap().is_none());
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn read_touch_now_needs_a_finger() {
+        let mut expectations = vec![i2c::Transaction::write_read(
+            testing::ADDRESS,
+            vec![0x01],
+            vec![0x05, 0x00, 0x00, 0x10, 0x00, 0x20],
+        )];
+        expectations.extend(testing::report(0x00, 0x30, 0x40));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        assert!(touchpad.read_touch_now().unwrap().is_none());
+        let event = touchpad.read_touch_now().unwrap().unwrap();
+        assert_eq!(event.point, TouchPoint::new(0x30, 0x40));
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn read_into_reuses_event() {
+        let report = testing::report(0x05, 0x10, 0x20);
+        let mut i2c_device = i2c::Mock::new(&report);
+        let mut int_pin = digital::Mock::new(&[
+            digital::Transaction::get(State::High),
+            digital::Transaction::get(State::Low),
+        ]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        let mut event = TouchEvent::default();
+        assert_eq!(touchpad.read_into(&mut event), Ok(false));
+        assert_eq!(event, TouchEvent::default());
+        assert_eq!(touchpad.read_into(&mut event), Ok(true));
+        assert_eq!(event.point, TouchPoint::new(0x10, 0x20));
+        assert_eq!(event.gesture, device::Gesture::SingleClick);
+        assert_eq!(event.sequence, 0);
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn pin_error_propagates() {
+        use embedded_hal::digital::ErrorKind;
+        use embedded_hal_mock::eh1::MockError;
+
+        let pin_error = || {
+            digital::Transaction::get(State::Low)
+                .with_error(MockError::Io(std::io::ErrorKind::NotConnected))
+        };
+        let mut i2c_device = i2c::Mock::new(&testing::report(0x05, 0x10, 0x20));
+        let mut int_pin = digital::Mock::new(&[pin_error(), pin_error(), pin_error()]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        let mut event = TouchEvent::default();
+        assert_eq!(
+            touchpad.read_into(&mut event),
+            Err(Error::Pin(ErrorKind::Other))
+        );
+        assert_eq!(touchpad.peek_event(), None);
+        touchpad.set_assume_ready_on_pin_error(true);
+        assert_eq!(touchpad.read_into(&mut event), Ok(true));
+        assert_eq!(event.point, TouchPoint::new(0x10, 0x20));
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn irq_pulse_width_read_back() {
+        let mut bank = mock::MockRegisterBank::new();
+        let mut touchpad = CST816S::new(&mut bank, DEFAULT_ADDRESS, NoIntPin, NoResetPin);
+
+        touchpad.set_irq_pulse_width(PulseWidth::new(200)).unwrap();
+        assert_eq!(touchpad.irq_pulse_width(), Ok(PulseWidth::new(200)));
+        assert_eq!(bank.get(0xED), 200);
+
+        bank.set(0xED, &[0]);
+        let mut touchpad = CST816S::new(&mut bank, DEFAULT_ADDRESS, NoIntPin, NoResetPin);
+        assert_eq!(touchpad.irq_pulse_width(), Err(Error::OutOfRange));
+    }
+
+    #[test]
+    async fn irq_pulse_width_in_micros() {
+        let mut bank = mock::MockRegisterBank::new();
+        let mut touchpad = CST816S::new(&mut bank, DEFAULT_ADDRESS, NoIntPin, NoResetPin);
+
+        touchpad.set_irq_pulse_width_us(500).unwrap();
+        assert_eq!(touchpad.irq_pulse_width().map(|p| p.as_us()), Ok(500));
+        assert_eq!(touchpad.set_irq_pulse_width_us(50), Err(Error::OutOfRange));
+        assert_eq!(
+            touchpad.set_irq_pulse_width_us(20_100),
+            Err(Error::OutOfRange)
+        );
+        assert_eq!(bank.get(0xED), 5);
+    }
+
+    #[test]
+    async fn read_chip_id_gated_by_interrupt() {
+        let mut i2c_device = i2c::Mock::new(&[
+            i2c::Transaction::write_read(0x15, vec![0xA7], vec![0xB4]),
+            i2c::Transaction::write_read(0x15, vec![0xA7], vec![0x00])
+                .with_error(embedded_hal::i2c::ErrorKind::Bus),
+        ]);
+        let mut int_pin = digital::Mock::new(&[
+            digital::Transaction::get(State::High),
+            digital::Transaction::get(State::Low),
+            digital::Transaction::get(State::Low),
+        ]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        assert_eq!(touchpad.read_chip_id(), Ok(None));
+        assert_eq!(touchpad.read_chip_id(), Ok(Some(0xB4)));
+        assert_eq!(
+            touchpad.read_chip_id(),
+            Err(Error::I2c(embedded_hal::i2c::ErrorKind::Bus))
+        );
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn read_point_with_flags_decodes_event() {
+        let mut i2c_device = i2c::Mock::new(&[
+            i2c::Transaction::write_read(0x15, vec![0x01], vec![0x00, 1, 0x01, 0x23, 0x00, 0x45]),
+            i2c::Transaction::write_read(0x15, vec![0x01], vec![0x00, 0, 0x41, 0x24, 0x00, 0x46]),
+            i2c::Transaction::write_read(0x15, vec![0x01], vec![0x00, 0, 0xC0, 0x00, 0x00, 0x00]),
+        ]);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        assert_eq!(
+            touchpad.read_point_with_flags().unwrap(),
+            Some((TouchPoint::new(0x123, 0x45), device::TouchEventType::Down))
+        );
+        assert_eq!(
+            touchpad.read_point_with_flags().unwrap(),
+            Some((TouchPoint::new(0x124, 0x46), device::TouchEventType::Up))
+        );
+        assert_eq!(touchpad.read_point_with_flags().unwrap(), None);
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn read_report_unchecked_with_touch() {
+        let mut i2c_device = i2c::Mock::new(&testing::report(0x00, 0x10, 0x20));
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        let event = touchpad.read_report_unchecked().unwrap().unwrap();
+        assert_eq!(event.point, TouchPoint::new(0x10, 0x20));
+        assert_eq!(event.gesture, device::Gesture::NoGesture);
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn touch_event_display() {
+        let mut i2c_device = i2c::Mock::new(&testing::report(0x0B, 0x10, 0x20));
+        let mut int_pin = digital::Mock::new(&[digital::Transaction::get(State::Low)]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        let event = touchpad.event().unwrap();
+        assert_eq!(event.to_string(), "double click at (16, 32)");
+        assert_eq!(
+            device::Gesture::Unknown(7).to_string(),
+            "unknown gesture 0x07"
+        );
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn last_event_tracks_reports() {
+        let mut expectations = testing::report(0x05, 0x10, 0x20);
+        expectations.extend(testing::report(0x01, 0x30, 0x40));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[
+            digital::Transaction::get(State::Low),
+            digital::Transaction::get(State::High),
+            digital::Transaction::get(State::Low),
+        ]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        assert!(touchpad.last_event().is_none());
+        touchpad.event().unwrap();
+        assert_eq!(
+            touchpad.last_event().unwrap().point,
+            TouchPoint::new(0x10, 0x20)
+        );
+        assert!(touchpad.event().is_none());
+        assert_eq!(
+            touchpad.last_event().unwrap().point,
+            TouchPoint::new(0x10, 0x20)
+        );
+        touchpad.event().unwrap();
+        assert_eq!(
+            touchpad.last_event().unwrap().point,
+            TouchPoint::new(0x30, 0x40)
+        );
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn take_last_event_clears() {
+        let mut i2c_device = i2c::Mock::new(&testing::report(0x05, 0x10, 0x20));
+        let mut int_pin = digital::Mock::new(&[digital::Transaction::get(State::Low)]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        touchpad.event().unwrap();
+        assert_eq!(
+            touchpad.take_last_event().unwrap().point,
+            TouchPoint::new(0x10, 0x20)
+        );
+        assert!(touchpad.take_last_event().is_none());
+        assert!(touchpad.last_event().is_none());
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn release_returns_bus_and_pins() {
+        let mut i2c_device =
+            i2c::Mock::new(&[i2c::Transaction::write(testing::ADDRESS, vec![0xE5, 0x03])]);
+        let mut int_pin = digital::Mock::new(&[digital::Transaction::get(State::High)]);
+        let mut rst_pin = digital::Mock::new(&[digital::Transaction::set(State::High)]);
+        let touchpad = CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        let (i2c, int, rst) = touchpad.release();
+        i2c.write(testing::ADDRESS, &[0xE5, 0x03]).unwrap();
+        assert!(int.is_high().unwrap());
+        rst.set_high().unwrap();
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn sequence_advances_per_report_and_wraps() {
+        let mut expectations = testing::report(0x05, 0x10, 0x20);
+        expectations.extend(testing::report(0x05, 0x10, 0x20));
+        expectations.extend(testing::report(0x05, 0x10, 0x20));
+        expectations.extend(testing::report(0x05, 0x10, 0x20));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&vec![digital::Transaction::get(State::Low); 4]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        assert_eq!(touchpad.peek_event().unwrap().sequence, 0);
+        assert_eq!(touchpad.event().unwrap().sequence, 0);
+        assert_eq!(touchpad.event().unwrap().sequence, 1);
+        touchpad.sequence = u32::MAX;
+        assert_eq!(touchpad.event().unwrap().sequence, u32::MAX);
+        assert_eq!(touchpad.sequence, 0);
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn origin_glitch_is_dropped_only_without_finger() {
+        let glitch = i2c::Transaction::write_read(
+            testing::ADDRESS,
+            vec![0x01],
+            vec![0x05, 0x00, 0x00, 0x00, 0x00, 0x00],
+        );
+        let mut expectations = vec![glitch.clone()];
+        expectations.extend(testing::report(0x05, 0x00, 0x00));
+        expectations.push(glitch);
+        expectations.extend(testing::report(0x05, 0x10, 0x20));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&vec![digital::Transaction::get(State::Low); 4]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+        touchpad.set_reject_origin_glitch(true);
+
+        assert!(touchpad.event().is_none());
+        assert_eq!(touchpad.event().unwrap().point, TouchPoint::new(0, 0));
+        let points: Vec<_> = touchpad
+            .events_with_limit(2)
+            .map(|event| event.unwrap().point)
+            .collect();
+        assert_eq!(points, [TouchPoint::new(0x10, 0x20)]);
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn shares_bus_with_other_devices() {
+        let mut expectations = vec![i2c::Transaction::write(0x3C, vec![0xAF])];
+        expectations.extend(testing::report(0x05, 0x10, 0x20));
+        expectations.push(i2c::Transaction::write(0x3C, vec![0xAE]));
+        let bus = core::cell::RefCell::new(i2c::Mock::new(&expectations));
+        let mut display = embedded_hal_bus::i2c::RefCellDevice::new(&bus);
+        let mut int_pin = digital::Mock::new(&[digital::Transaction::get(State::Low)]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad = CST816S::new(
+            embedded_hal_bus::i2c::RefCellDevice::new(&bus),
+            DEFAULT_ADDRESS,
+            &mut int_pin,
+            &mut rst_pin,
+        );
+
+        display.write(0x3C, &[0xAF]).unwrap();
+        assert_eq!(touchpad.event().unwrap().point, TouchPoint::new(0x10, 0x20));
+        display.write(0x3C, &[0xAE]).unwrap();
+
+        let _ = touchpad.release();
+        bus.borrow_mut().done();
+        int_pin.done();
+        rst_pin.done();
+    }
 }
@@ -0,0 +1,212 @@
+//! # In-memory register bank for tests
+//!
+//! Only available with the `test-util` feature.
+use embedded_hal::i2c::{self, ErrorKind, NoAcknowledgeSource, Operation, SevenBitAddress};
+
+use crate::{Address, DEFAULT_ADDRESS};
+
+/// A fake CST816 holding its 256 registers in memory.
+///
+/// It implements [`I2c`](embedded_hal::i2c::I2c), so it can be handed to
+/// [`CST816S::new`](crate::CST816S::new) in place of the bus, and
+/// [`device_driver::RegisterInterface`] for tests against the low level
+/// [`Device`](crate::device::Device). Tests set register contents with [`MockRegisterBank::set`],
+/// call the high level methods and check the result with [`MockRegisterBank::get`], without
+/// listing every bus transaction.
+///
+/// Like the chip, the first byte written in a transaction selects the register and every
+/// further byte read or written moves on to the next one. Register side effects, like clearing
+/// the report after a read, are not modelled.
+///
+/// ```
+/// use cst816s_device_driver::{CST816S, DEFAULT_ADDRESS, mock::MockRegisterBank};
+///
+/// let mut bank = MockRegisterBank::new();
+/// bank.set(0x01, &[0x05, 1, 0x00, 0x10, 0x00, 0x20]);
+/// let mut touchpad = CST816S::new_polling(&mut bank, DEFAULT_ADDRESS, ());
+///
+/// let event = touchpad.read_touch_now().unwrap().unwrap();
+/// assert_eq!((event.point.x, event.point.y), (0x10, 0x20));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockRegisterBank {
+    address: Address,
+    registers: [u8; 256],
+    asleep: bool,
+}
+
+impl Default for MockRegisterBank {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockRegisterBank {
+    /// A bank at [`DEFAULT_ADDRESS`] with every register 0.
+    pub const fn new() -> Self {
+        Self::with_address(DEFAULT_ADDRESS)
+    }
+
+    /// A bank answering at `address` with every register 0.
+    pub const fn with_address(address: Address) -> Self {
+        Self {
+            address,
+            registers: [0; 256],
+            asleep: false,
+        }
+    }
+
+    /// Set the registers starting at `register` to `data`.
+    pub fn set(&mut self, register: u8, data: &[u8]) {
+        let start = usize::from(register);
+        self.registers[start..start + data.len()].copy_from_slice(data);
+    }
+
+    /// The register at `register`.
+    pub fn get(&self, register: u8) -> u8 {
+        self.registers[usize::from(register)]
+    }
+
+    /// Make every transfer fail with a NACK, like a chip in low power mode.
+    pub fn set_asleep(&mut self, asleep: bool) {
+        self.asleep = asleep;
+    }
+}
+
+impl i2c::ErrorType for MockRegisterBank {
+    type Error = ErrorKind;
+}
+
+impl i2c::I2c for MockRegisterBank {
+    fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        if self.asleep || address != self.address.get() {
+            return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+        }
+        let mut pointer: Option<u8> = None;
+        for operation in operations {
+            match operation {
+                Operation::Write(bytes) => {
+                    for &byte in bytes.iter() {
+                        match pointer {
+                            None => pointer = Some(byte),
+                            Some(register) => {
+                                self.registers[usize::from(register)] = byte;
+                                pointer = Some(register.wrapping_add(1));
+                            }
+                        }
+                    }
+                }
+                Operation::Read(buffer) => {
+                    let mut register = pointer.unwrap_or(0);
+                    for byte in buffer.iter_mut() {
+                        *byte = self.registers[usize::from(register)];
+                        register = register.wrapping_add(1);
+                    }
+                    pointer = Some(register);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl device_driver::RegisterInterface for MockRegisterBank {
+    type Error = core::convert::Infallible;
+
+    type AddressType = u8;
+
+    fn write_register(
+        &mut self,
+        address: Self::AddressType,
+        _size_bits: u32,
+        data: &[u8],
+    ) -> Result<(), Self::Error> {
+        self.set(address, data);
+        Ok(())
+    }
+
+    fn read_register(
+        &mut self,
+        address: Self::AddressType,
+        _size_bits: u32,
+        data: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        let start = usize::from(address);
+        data.copy_from_slice(&self.registers[start..start + data.len()]);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        CST816S, ChipVariant, FirmwareInfo, IrqConfig, NoIntPin, NoResetPin, ScanPeriod,
+        TouchPoint, device::Device,
+    };
+
+    #[test]
+    fn polls_event() {
+        let mut bank = MockRegisterBank::new();
+        bank.set(0x01, &[0x01, 1, 0x00, 0x64, 0x00, 0xC8]);
+        let mut touchpad = CST816S::new_polling(&mut bank, DEFAULT_ADDRESS, ());
+
+        let event = touchpad.event().unwrap();
+        assert_eq!(event.point, TouchPoint::new(100, 200));
+
+        bank.set_asleep(true);
+        let mut touchpad = CST816S::new_polling(&mut bank, DEFAULT_ADDRESS, ());
+        assert!(touchpad.event().is_none());
+    }
+
+    #[test]
+    fn reads_firmware_info() {
+        let mut bank = MockRegisterBank::new();
+        bank.set(0xA7, &[0xB6, 0x01, 0x03]);
+        let mut touchpad = CST816S::new_polling(&mut bank, DEFAULT_ADDRESS, ());
+
+        assert_eq!(
+            touchpad.firmware_info(),
+            Ok(FirmwareInfo {
+                variant: ChipVariant::Cst816D,
+                project_id: 0x01,
+                fw_version: 0x03,
+            })
+        );
+    }
+
+    #[test]
+    fn config_round_trip() {
+        let mut bank = MockRegisterBank::new();
+        let mut touchpad = CST816S::new(&mut bank, DEFAULT_ADDRESS, NoIntPin, NoResetPin);
+        let config = IrqConfig {
+            once_wlp: true,
+            en_motion: false,
+            en_change: true,
+            en_touch: true,
+            en_test: false,
+        };
+
+        touchpad.set_irq_config(config).unwrap();
+        assert_eq!(touchpad.irq_config(), Ok(config));
+        assert_eq!(bank.get(0xFA), 0b0110_0001);
+    }
+
+    #[test]
+    fn low_level_device() {
+        let mut bank = MockRegisterBank::new();
+        bank.set(0x03, &[0x01, 0x02]);
+        let mut device = Device::new(bank);
+
+        assert_eq!(device.xpos().read().unwrap().value(), 0x0102);
+        device
+            .nor_scan_per()
+            .write(|m| m.set_value(ScanPeriod::try_new(3).unwrap()))
+            .unwrap();
+        assert_eq!(device.interface.get(0xEE), 3);
+    }
+}
@@ -0,0 +1,82 @@
+//! # Two finger reports
+use embedded_hal::i2c::I2c;
+use heapless::Vec;
+
+use crate::{CST816S, Error, TouchEvent, TouchReady, device::Gesture};
+
+impl<I2C, TPINT, TPRST> CST816S<I2C, TPINT, TPRST>
+where
+    I2C: I2c,
+    TPINT: TouchReady,
+{
+    /// Read every contact of the pending report.
+    ///
+    /// The datasheet only documents `FingerNum` values of 0 and 1, but some firmware revisions
+    /// report 2 and put the second contact in the extended block at 0x07 to 0x0C. This reads
+    /// the normal report first and only reads the extended block when `FingerNum` is 2, so on
+    /// chips without it this costs nothing and returns at most one event. Support can be
+    /// detected by touching with two fingers and checking for a second event.
+    ///
+    /// The first event is the one [`CST816S::event`] would return. The second one goes through
+    /// the same [`Orientation`](crate::Orientation) and bounds handling but not the debounce,
+    /// and has no gesture or `BPC` values. Both share the same [`TouchEvent::sequence`]. The
+    /// report is acknowledged.
+    pub fn event_multi(&mut self) -> Result<Vec<TouchEvent, 2>, Error<I2C::Error>> {
+        let mut events = Vec::new();
+        if !self.interrupt_pending()? {
+            return Ok(events);
+        }
+        let data = self.read_touch_data()?;
+        let fingers = data.finger_num();
+        if let Some(event) = self.decode_report(data)? {
+            events.push(event).ok();
+        }
+        if fingers >= 2 {
+            let second = self.device.second_touch_data().read()?;
+            if let Some(point) = self.to_display(self.raw_point(second.x(), second.y())) {
+                events
+                    .push(TouchEvent {
+                        point,
+                        bpc0: None,
+                        bpc1: None,
+                        gesture: Gesture::NoGesture,
+                        event_type: second.event(),
+                        sequence: self.sequence,
+                    })
+                    .ok();
+            }
+        }
+        self.acknowledge();
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DEFAULT_ADDRESS, TouchPoint, device::TouchEventType, mock::MockRegisterBank};
+
+    #[test]
+    fn reads_second_contact() {
+        let mut bank = MockRegisterBank::new();
+        bank.set(
+            0x01,
+            &[
+                0x00, 2, 0x80, 0x10, 0x00, 0x20, 0x00, 0x30, 0x00, 0x40, 0x00, 0x00,
+            ],
+        );
+        let mut touchpad = CST816S::new_polling(&mut bank, DEFAULT_ADDRESS, ());
+
+        let events = touchpad.event_multi().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].point, TouchPoint::new(0x10, 0x20));
+        assert_eq!(events[0].event_type, TouchEventType::Contact);
+        assert_eq!(events[1].point, TouchPoint::new(0x30, 0x40));
+        assert_eq!(events[1].event_type, TouchEventType::Down);
+        assert_eq!(events[1].sequence, events[0].sequence);
+
+        bank.set(0x02, &[1]);
+        let mut touchpad = CST816S::new_polling(&mut bank, DEFAULT_ADDRESS, ());
+        assert_eq!(touchpad.event_multi().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,270 @@
+//! # Touch coordinates
+#[cfg(any(feature = "float", feature = "embedded-graphics"))]
+use crate::TouchEvent;
+
+/// Combine the `XposH`/`XposL` or `YposH`/`YposL` register pair into a 12 bit coordinate.
+///
+/// The high register holds bits 8 to 11 of the coordinate in its low nibble, its upper bits
+/// are flags (the event flag in bits 6 and 7 of `XposH`) and are dropped. The low register
+/// holds bits 0 to 7. So `high` is shifted by 8; shifting it by 2, as some older drivers do,
+/// mixes it into the low byte. This matches the `Xpos`, `Ypos` and `TouchData` registers of the
+/// register map, for code reading the halves one by one.
+///
+/// ```
+/// use cst816s_device_driver::combine_coord;
+///
+/// assert_eq!(combine_coord(0x01, 0x02), 0x0102);
+/// assert_eq!(combine_coord(0x80, 0xEF), 0x00EF);
+/// ```
+pub const fn combine_coord(high: u8, low: u8) -> u16 {
+    ((high & 0x0F) as u16) << 8 | low as u16
+}
+
+/// Where on the panel a touch was registered, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct TouchPoint {
+    /// Horizontal position, growing to the right
+    pub x: u16,
+    /// Vertical position, growing downwards
+    pub y: u16,
+}
+
+impl TouchPoint {
+    /// Create a point from its coordinates.
+    pub const fn new(x: u16, y: u16) -> Self {
+        Self { x, y }
+    }
+
+    /// Horizontal position.
+    pub const fn x(&self) -> u16 {
+        self.x
+    }
+
+    /// Vertical position.
+    pub const fn y(&self) -> u16 {
+        self.y
+    }
+
+    /// Squared euclidean distance to `other`.
+    ///
+    /// Cheap to compute without floating point, and fine for comparing distances.
+    pub const fn distance_squared_to(&self, other: TouchPoint) -> u64 {
+        let dx = self.x.abs_diff(other.x) as u64;
+        let dy = self.y.abs_diff(other.y) as u64;
+        dx * dx + dy * dy
+    }
+
+    /// Chebyshev distance to `other`, the larger of the x and y differences.
+    pub(crate) const fn chebyshev_distance(&self, other: TouchPoint) -> u16 {
+        let dx = self.x.abs_diff(other.x);
+        let dy = self.y.abs_diff(other.y);
+        if dx > dy { dx } else { dy }
+    }
+
+    /// Signed offset `(dx, dy)` of this point from `origin`.
+    pub const fn offset_from(&self, origin: TouchPoint) -> (i32, i32) {
+        (
+            self.x as i32 - origin.x as i32,
+            self.y as i32 - origin.y as i32,
+        )
+    }
+}
+
+impl From<(u16, u16)> for TouchPoint {
+    fn from((x, y): (u16, u16)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl From<TouchPoint> for (u16, u16) {
+    fn from(point: TouchPoint) -> Self {
+        (point.x, point.y)
+    }
+}
+
+impl core::fmt::Display for TouchPoint {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// A touch position scaled to the panel size, both axes in `0.0..=1.0`.
+///
+/// Returned by [`TouchPoint::normalized`] and [`TouchEvent::normalized`], so the same UI code
+/// can run on panels with different resolutions. Only available with the `float` feature.
+#[cfg(feature = "float")]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct NormalizedPoint {
+    /// 0.0 at the left edge, 1.0 at the right edge
+    pub x: f32,
+    /// 0.0 at the top edge, 1.0 at the bottom edge
+    pub y: f32,
+}
+
+#[cfg(feature = "float")]
+impl From<NormalizedPoint> for (f32, f32) {
+    fn from(point: NormalizedPoint) -> Self {
+        (point.x, point.y)
+    }
+}
+
+#[cfg(feature = "float")]
+impl TouchPoint {
+    /// Scale the point to a `width` x `height` panel.
+    ///
+    /// The last pixel of each axis maps to 1.0. Raw values beyond the panel are clamped to 1.0,
+    /// a zero sized axis always maps to 0.0.
+    pub fn normalized(&self, width: u16, height: u16) -> NormalizedPoint {
+        fn axis(value: u16, size: u16) -> f32 {
+            let last = size.saturating_sub(1);
+            if last == 0 {
+                return 0.0;
+            }
+            f32::from(value.min(last)) / f32::from(last)
+        }
+        NormalizedPoint {
+            x: axis(self.x, width),
+            y: axis(self.y, height),
+        }
+    }
+}
+
+#[cfg(feature = "float")]
+impl TouchEvent {
+    /// The touch position scaled to a `width` x `height` panel, see [`TouchPoint::normalized`].
+    pub fn normalized(&self, width: u16, height: u16) -> NormalizedPoint {
+        self.point.normalized(width, height)
+    }
+}
+
+#[cfg(feature = "embedded-graphics")]
+impl From<TouchPoint> for embedded_graphics_core::geometry::Point {
+    fn from(point: TouchPoint) -> Self {
+        Self::new(i32::from(point.x), i32::from(point.y))
+    }
+}
+
+#[cfg(feature = "embedded-graphics")]
+impl From<&TouchEvent> for embedded_graphics_core::geometry::Point {
+    fn from(event: &TouchEvent) -> Self {
+        event.point.into()
+    }
+}
+
+#[cfg(feature = "embedded-graphics")]
+impl TouchEvent {
+    /// The touch position as an `embedded-graphics` point, ready for drawing or hit-testing.
+    ///
+    /// Only available with the `embedded-graphics` feature.
+    ///
+    /// ```
+    /// # use cst816s_device_driver::{TouchEvent, TouchPoint, device::{Gesture, TouchEventType}};
+    /// use embedded_graphics_core::{
+    ///     geometry::{Point, Size},
+    ///     primitives::Rectangle,
+    /// };
+    ///
+    /// # let event = TouchEvent {
+    /// #     point: TouchPoint::new(60, 200),
+    /// #     bpc0: None,
+    /// #     bpc1: None,
+    /// #     gesture: Gesture::SingleClick,
+    /// #     event_type: TouchEventType::Contact,
+    /// #     sequence: 0,
+    /// # };
+    /// let button = Rectangle::new(Point::new(40, 180), Size::new(80, 40));
+    /// assert!(button.contains(event.eg_point()));
+    /// ```
+    pub fn eg_point(&self) -> embedded_graphics_core::geometry::Point {
+        self.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combine_coord_bit_math() {
+        let cases = [
+            (0x00, 0x00, 0x000),
+            (0x00, 0xEF, 0x0EF),
+            (0x01, 0x02, 0x102),
+            (0x01, 0x0E, 0x10E),
+            (0x0F, 0xFF, 0xFFF),
+            // Event flag and reserved bits of `XposH` are not part of the coordinate
+            (0xC3, 0x45, 0x345),
+            (0x40, 0x10, 0x010),
+        ];
+        for (high, low, expected) in cases {
+            assert_eq!(combine_coord(high, low), expected, "{high:#04x} {low:#04x}");
+        }
+        assert_ne!(combine_coord(0x01, 0x02), (0x01 << 2) | 0x02);
+    }
+
+    #[test]
+    fn distance_and_offset() {
+        let a = TouchPoint::new(10, 20);
+        let b = TouchPoint::new(13, 16);
+
+        assert_eq!(a.distance_squared_to(b), 25);
+        assert_eq!(b.distance_squared_to(a), 25);
+        assert_eq!(b.offset_from(a), (3, -4));
+        assert_eq!(a.offset_from(b), (-3, 4));
+
+        let far = TouchPoint::new(u16::MAX, u16::MAX);
+        assert_eq!(
+            TouchPoint::new(0, 0).distance_squared_to(far),
+            2 * 65_535 * 65_535
+        );
+    }
+
+    #[test]
+    fn tuple_conversions() {
+        let point = TouchPoint::from((1, 2));
+        assert_eq!(point, TouchPoint { x: 1, y: 2 });
+        assert_eq!((point.x(), point.y()), (1, 2));
+        assert_eq!(<(u16, u16)>::from(point), (1, 2));
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn normalized_scales_and_clamps() {
+        let size = 240;
+        assert_eq!(
+            TouchPoint::new(0, 239).normalized(size, size),
+            NormalizedPoint { x: 0.0, y: 1.0 }
+        );
+        assert_eq!(TouchPoint::new(273, 0).normalized(size, size).x, 1.0);
+        let middle = TouchPoint::new(119, 119).normalized(size, size);
+        assert!((middle.x - 0.5).abs() < 0.01);
+        assert_eq!(
+            TouchPoint::new(5, 5).normalized(0, 1),
+            NormalizedPoint { x: 0.0, y: 0.0 }
+        );
+    }
+
+    #[cfg(feature = "embedded-graphics")]
+    #[test]
+    fn embedded_graphics_point() {
+        use crate::device::{Gesture, TouchEventType};
+        use embedded_graphics_core::geometry::Point;
+
+        let event = TouchEvent {
+            point: TouchPoint::new(12, 240),
+            bpc0: None,
+            bpc1: None,
+            gesture: Gesture::SingleClick,
+            event_type: TouchEventType::Contact,
+            sequence: 0,
+        };
+        assert_eq!(event.eg_point(), Point::new(12, 240));
+        assert_eq!(Point::from(&event), Point::new(12, 240));
+        assert_eq!(
+            Point::from(TouchPoint::new(u16::MAX, 0)),
+            Point::new(65535, 0)
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! # Polling mode without an interrupt pin
+use embedded_hal::{digital::InputPin, i2c::I2c};
+
+use crate::{Address, CST816S, Error, error::is_nack};
+
+/// Placeholder for the interrupt pin on boards that do not route it to the MCU.
+///
+/// Instead of checking the pin, the driver reads the `FingerNum` register to decide whether a
+/// report is ready, so [`CST816S::event`], [`CST816S::events`] and
+/// [`CST816S::wait_for_touch`] work as usual. Every check costs an I2C transfer, where the
+/// interrupt pin costs a GPIO read, and a report then takes a second transfer. Gestures that the
+/// chip reports after the finger was lifted are not seen this way; use
+/// [`CST816S::read_report_unchecked`] to catch those.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NoIntPin;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Tells the driver whether a touch report is ready to be read.
+///
+/// Implemented for every [`InputPin`], which signals a report by going low, and for
+/// [`NoIntPin`], which reads the `FingerNum` register instead. This trait is sealed.
+pub trait TouchReady: sealed::Sealed + Sized {
+    /// Whether a report is ready. A chip that went to sleep and does not answer has none.
+    #[doc(hidden)]
+    fn touch_ready<I2C: I2c, TPRST>(
+        driver: &mut CST816S<I2C, Self, TPRST>,
+    ) -> Result<bool, Error<I2C::Error>>;
+}
+
+impl<P: InputPin> sealed::Sealed for P {}
+
+impl<P: InputPin> TouchReady for P {
+    fn touch_ready<I2C: I2c, TPRST>(
+        driver: &mut CST816S<I2C, Self, TPRST>,
+    ) -> Result<bool, Error<I2C::Error>> {
+        driver.interrupt_pin.is_low().map_err(Error::pin)
+    }
+}
+
+impl sealed::Sealed for NoIntPin {}
+
+impl TouchReady for NoIntPin {
+    fn touch_ready<I2C: I2c, TPRST>(
+        driver: &mut CST816S<I2C, Self, TPRST>,
+    ) -> Result<bool, Error<I2C::Error>> {
+        match driver.device.finger_num().read() {
+            Ok(fingers) => Ok(fingers.value() > 0),
+            Err(e) if is_nack(&e.0) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl<I2C, TPRST> CST816S<I2C, NoIntPin, TPRST> {
+    /// Create a driver without an interrupt pin.
+    ///
+    /// Touch reports are read by polling the registers, see [`NoIntPin`] for the extra bus
+    /// traffic and [`CST816S::read_report_unchecked`] for the power implications.
+    pub fn new_polling(i2c: I2C, address: Address, reset_pin: TPRST) -> Self {
+        Self::new(i2c, address, NoIntPin, reset_pin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DEFAULT_ADDRESS, TouchPoint, device::Gesture, testing};
+    use embedded_hal_mock::eh1::{
+        delay::{CheckedDelay, Transaction as DelayTransaction},
+        digital, i2c,
+    };
+    use futures_test::test;
+
+    fn finger_num(fingers: u8) -> i2c::Transaction {
+        i2c::Transaction::write_read(testing::ADDRESS, vec![0x02], vec![fingers])
+    }
+
+    #[test]
+    async fn polling_driver_reads_without_pin() {
+        let mut expectations = vec![finger_num(0), finger_num(1)];
+        expectations.extend(testing::report(0x05, 0x10, 0x20));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut delay = CheckedDelay::new(&[DelayTransaction::blocking_delay_ms(5)]);
+        let mut touchpad = CST816S::new_polling(&mut i2c_device, DEFAULT_ADDRESS, &mut rst_pin);
+
+        let event = touchpad.wait_for_touch(&mut delay, 100).unwrap().unwrap();
+        assert_eq!(event.point, TouchPoint::new(0x10, 0x20));
+        assert_eq!(event.gesture, Gesture::SingleClick);
+
+        i2c_device.done();
+        rst_pin.done();
+        delay.done();
+    }
+
+    #[test]
+    async fn polling_driver_event_checks_finger_num() {
+        let mut expectations = vec![finger_num(0), finger_num(1)];
+        expectations.extend(testing::report(0x00, 0x10, 0x20));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad = CST816S::new_polling(&mut i2c_device, DEFAULT_ADDRESS, &mut rst_pin);
+
+        assert!(touchpad.event().is_none());
+        assert_eq!(touchpad.event().unwrap().point, TouchPoint::new(0x10, 0x20));
+
+        i2c_device.done();
+        rst_pin.done();
+    }
+}
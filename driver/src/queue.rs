@@ -0,0 +1,330 @@
+//! # Buffered event queue
+use core::ops::{Deref, DerefMut};
+
+use embedded_hal::i2c::I2c;
+use heapless::Deque;
+
+use crate::{CST816S, Error, TouchEvent, TouchReady};
+
+/// Wrapper around [`CST816S`] that buffers up to `N` touch events.
+///
+/// Call [`BufferedCST816S::pump`] to move pending hardware reports into the queue, then drain
+/// them with [`BufferedCST816S::pop_event`], e.g. once per rendered frame. When the queue is
+/// full the oldest event is dropped to make room, and [`BufferedCST816S::dropped`] counts how
+/// many events were lost that way.
+///
+/// For interrupt driven designs, call [`BufferedCST816S::handle_interrupt`] from the GPIO
+/// interrupt handler instead of polling with [`BufferedCST816S::pump`].
+///
+/// All other driver methods stay reachable through [`Deref`]/[`DerefMut`].
+pub struct BufferedCST816S<I2C, TPINT, TPRST, const N: usize> {
+    driver: CST816S<I2C, TPINT, TPRST>,
+    queue: EventQueue<N>,
+}
+
+/// Fixed capacity queue with a drop-oldest overflow policy.
+struct EventQueue<const N: usize> {
+    events: Deque<TouchEvent, N>,
+    dropped: u32,
+}
+
+impl<const N: usize> EventQueue<N> {
+    const fn new() -> Self {
+        Self {
+            events: Deque::new(),
+            dropped: 0,
+        }
+    }
+
+    fn push(&mut self, event: TouchEvent) {
+        if self.events.is_full() {
+            self.events.pop_front();
+            self.dropped = self.dropped.saturating_add(1);
+        }
+        // Cannot fail, there is room after dropping the oldest event.
+        let _ = self.events.push_back(event);
+    }
+}
+
+impl<I2C, TPINT, TPRST, const N: usize> BufferedCST816S<I2C, TPINT, TPRST, N> {
+    /// Wrap a driver with an empty queue.
+    pub fn new(driver: CST816S<I2C, TPINT, TPRST>) -> Self {
+        Self {
+            driver,
+            queue: EventQueue::new(),
+        }
+    }
+
+    /// Take the oldest queued event.
+    pub fn pop_event(&mut self) -> Option<TouchEvent> {
+        self.queue.events.pop_front()
+    }
+
+    /// Number of events currently queued.
+    pub fn len(&self) -> usize {
+        self.queue.events.len()
+    }
+
+    /// Whether the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.queue.events.is_empty()
+    }
+
+    /// Number of events dropped because the queue was full. Saturates at `u32::MAX`.
+    pub fn dropped(&self) -> u32 {
+        self.queue.dropped
+    }
+
+    /// Reset the dropped events counter.
+    pub fn clear_dropped(&mut self) {
+        self.queue.dropped = 0;
+    }
+
+    /// Unwrap the driver, discarding any queued events.
+    pub fn into_inner(self) -> CST816S<I2C, TPINT, TPRST> {
+        self.driver
+    }
+}
+
+impl<I2C, TPINT, TPRST, const N: usize> BufferedCST816S<I2C, TPINT, TPRST, N>
+where
+    I2C: I2c,
+    TPINT: TouchReady,
+{
+    /// Read all pending hardware reports into the queue.
+    ///
+    /// Uses [`CST816S::events`] and returns the number of events that were queued.
+    pub fn pump(&mut self) -> Result<usize, Error<I2C::Error>> {
+        let mut count = 0;
+        for event in self.driver.events() {
+            self.queue.push(event?);
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Read the current report into the queue, meant to be called from the touch interrupt handler.
+    ///
+    /// This does not check the interrupt pin, the interrupt firing already tells us a report is
+    /// ready. It never allocates and performs a fixed number of register reads, so its execution
+    /// time is bounded. For that reason it skips the reset check of
+    /// [`CST816S::set_auto_reapply`]; call [`CST816S::reapply_config_if_reset`] from the main
+    /// loop instead. It is safe to call from an ISR as long as the driver is only reachable
+    /// through a critical section or an RTIC resource, so the main loop can't be in the middle
+    /// of another bus transaction.
+    ///
+    /// ```ignore
+    /// static TOUCH: Mutex<RefCell<Option<BufferedCST816S<I2C, TPINT, TPRST, 8>>>> =
+    ///     Mutex::new(RefCell::new(None));
+    ///
+    /// #[interrupt]
+    /// fn IO_IRQ_BANK0() {
+    ///     critical_section::with(|cs| {
+    ///         // Clear the GPIO interrupt flag using your HAL first.
+    ///         if let Some(touchpad) = TOUCH.borrow_ref_mut(cs).as_mut() {
+    ///             let _ = touchpad.handle_interrupt();
+    ///         }
+    ///     });
+    /// }
+    ///
+    /// // main loop
+    /// loop {
+    ///     while let Some(event) =
+    ///         critical_section::with(|cs| TOUCH.borrow_ref_mut(cs).as_mut()?.pop_event())
+    ///     {
+    ///         // handle event
+    ///     }
+    /// }
+    /// ```
+    pub fn handle_interrupt(&mut self) -> Result<(), Error<I2C::Error>> {
+        if let Some(event) = self.driver.read_report_without_reapply()? {
+            self.driver.acknowledge();
+            self.queue.push(event);
+        }
+        Ok(())
+    }
+}
+
+impl<I2C, TPINT, TPRST, const N: usize> Deref for BufferedCST816S<I2C, TPINT, TPRST, N> {
+    type Target = CST816S<I2C, TPINT, TPRST>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.driver
+    }
+}
+
+impl<I2C, TPINT, TPRST, const N: usize> DerefMut for BufferedCST816S<I2C, TPINT, TPRST, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.driver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_hal_mock::eh1::{
+        digital::{self, State},
+        i2c,
+    };
+    use futures_test::test;
+
+    use crate::{DEFAULT_ADDRESS, TouchPoint, testing::report};
+
+    #[test]
+    async fn pump_drops_oldest_on_overflow() {
+        let expectations: Vec<_> = [1, 2, 3]
+            .into_iter()
+            .flat_map(|x| report(0x00, x, 0x20))
+            .collect();
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[
+            digital::Transaction::get(State::Low),
+            digital::Transaction::get(State::Low),
+            digital::Transaction::get(State::Low),
+            digital::Transaction::get(State::High),
+        ]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad: BufferedCST816S<_, _, _, 2> = BufferedCST816S::new(CST816S::new(
+            &mut i2c_device,
+            DEFAULT_ADDRESS,
+            &mut int_pin,
+            &mut rst_pin,
+        ));
+
+        assert_eq!(touchpad.pump().unwrap(), 3);
+        assert_eq!(touchpad.len(), 2);
+        assert_eq!(touchpad.dropped(), 1);
+        assert_eq!(
+            touchpad.pop_event().unwrap().point,
+            TouchPoint::new(2, 0x20)
+        );
+        assert_eq!(
+            touchpad.pop_event().unwrap().point,
+            TouchPoint::new(3, 0x20)
+        );
+        assert!(touchpad.pop_event().is_none());
+
+        drop(touchpad);
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn queue_wraps_around() {
+        let expectations: Vec<_> = [1, 2, 3, 4]
+            .into_iter()
+            .flat_map(|x| report(0x00, x, 0x20))
+            .collect();
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[
+            digital::Transaction::get(State::Low),
+            digital::Transaction::get(State::Low),
+            digital::Transaction::get(State::High),
+            digital::Transaction::get(State::Low),
+            digital::Transaction::get(State::Low),
+            digital::Transaction::get(State::High),
+        ]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad: BufferedCST816S<_, _, _, 3> = BufferedCST816S::new(CST816S::new(
+            &mut i2c_device,
+            DEFAULT_ADDRESS,
+            &mut int_pin,
+            &mut rst_pin,
+        ));
+
+        touchpad.pump().unwrap();
+        assert_eq!(
+            touchpad.pop_event().unwrap().point,
+            TouchPoint::new(1, 0x20)
+        );
+        touchpad.pump().unwrap();
+        assert_eq!(touchpad.dropped(), 0);
+        assert_eq!(
+            touchpad.pop_event().unwrap().point,
+            TouchPoint::new(2, 0x20)
+        );
+        assert_eq!(
+            touchpad.pop_event().unwrap().point,
+            TouchPoint::new(3, 0x20)
+        );
+        assert_eq!(
+            touchpad.pop_event().unwrap().point,
+            TouchPoint::new(4, 0x20)
+        );
+        assert!(touchpad.is_empty());
+
+        drop(touchpad);
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn handle_interrupt_pushes_without_pin_check() {
+        let expectations: Vec<_> = [1, 2]
+            .into_iter()
+            .flat_map(|x| report(0x00, x, 0x20))
+            .collect();
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad: BufferedCST816S<_, _, _, 4> = BufferedCST816S::new(CST816S::new(
+            &mut i2c_device,
+            DEFAULT_ADDRESS,
+            &mut int_pin,
+            &mut rst_pin,
+        ));
+
+        assert!(touchpad.pop_event().is_none());
+        touchpad.handle_interrupt().unwrap();
+        touchpad.handle_interrupt().unwrap();
+        assert_eq!(
+            touchpad.pop_event().unwrap().point,
+            TouchPoint::new(1, 0x20)
+        );
+        assert_eq!(
+            touchpad.pop_event().unwrap().point,
+            TouchPoint::new(2, 0x20)
+        );
+        assert!(touchpad.pop_event().is_none());
+
+        drop(touchpad);
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn handle_interrupt_skips_auto_reapply() {
+        let mut expectations = Vec::new();
+        expectations.extend(crate::testing::write(0xFA, &[0b0111_0001]));
+        expectations.extend(crate::testing::write(0xEC, &[0b0000_0111]));
+        expectations.extend(crate::testing::write(0xFE, &[0xFE]));
+        expectations.extend(crate::testing::write(0xED, &[0x01]));
+        expectations.extend(crate::testing::write(0xEE, &[0x01]));
+        expectations.extend(report(0x00, 0x10, 0x20));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad: BufferedCST816S<_, _, _, 2> = BufferedCST816S::new(CST816S::new(
+            &mut i2c_device,
+            DEFAULT_ADDRESS,
+            &mut int_pin,
+            &mut rst_pin,
+        ));
+        touchpad.init_config().unwrap();
+        touchpad.set_auto_reapply(true);
+
+        touchpad.handle_interrupt().unwrap();
+        assert_eq!(
+            touchpad.pop_event().unwrap().point,
+            TouchPoint::new(0x10, 0x20)
+        );
+
+        drop(touchpad);
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+}
@@ -0,0 +1,391 @@
+//! # Hit-testing regions
+//!
+//! Shapes to check touches against, using integer math only so they work on cores without a
+//! floating point unit. All shapes include their edges.
+//!
+//! Angles are in millidegrees. 0° points right (growing x) and angles grow clockwise on the
+//! screen, as the y axis grows downwards: 90° points down, 180° left and 270° up.
+use crate::TouchPoint;
+
+/// A full circle in millidegrees.
+const FULL_TURN: u32 = 360_000;
+
+/// A disc around `center`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Circle {
+    /// Center of the circle
+    pub center: TouchPoint,
+    /// Radius in pixels
+    pub radius: u16,
+}
+
+impl Circle {
+    /// Whether `point` is at most `radius` pixels away from the center.
+    pub fn contains(&self, point: &TouchPoint) -> bool {
+        let radius = u64::from(self.radius);
+        point.distance_squared_to(self.center) <= radius * radius
+    }
+}
+
+/// An axis aligned rectangle spanning `min` to `max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Rect {
+    /// Top left corner
+    pub min: TouchPoint,
+    /// Bottom right corner
+    pub max: TouchPoint,
+}
+
+impl Rect {
+    /// Whether `point` is inside the rectangle.
+    pub fn contains(&self, point: &TouchPoint) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+}
+
+/// A segment of a ring around `center`, for the buttons along the edge of round displays.
+///
+/// The segment runs clockwise from `start_millideg` to `end_millideg`, both in `0..=360_000`.
+/// When `end_millideg` is smaller it wraps through 0°, so 315° to 45° is the quarter on the
+/// right. 0 to 360 000 is the full ring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct ArcSegment {
+    /// Center of the ring
+    pub center: TouchPoint,
+    /// Inner radius in pixels, 0 for a pie slice
+    pub inner_r: u16,
+    /// Outer radius in pixels
+    pub outer_r: u16,
+    /// Angle the segment starts at
+    pub start_millideg: u32,
+    /// Angle the segment ends at
+    pub end_millideg: u32,
+}
+
+impl ArcSegment {
+    /// Whether `point` is inside the segment.
+    ///
+    /// The angle of a point is approximated to within 0.1°. The center itself has no angle and
+    /// is only inside a segment with an `inner_r` of 0.
+    pub fn contains(&self, point: &TouchPoint) -> bool {
+        let distance = point.distance_squared_to(self.center);
+        let inner = u64::from(self.inner_r);
+        let outer = u64::from(self.outer_r);
+        if distance < inner * inner || distance > outer * outer {
+            return false;
+        }
+        if distance == 0 {
+            return true;
+        }
+
+        let start = self.start_millideg % FULL_TURN;
+        let end = self.end_millideg % FULL_TURN;
+        if self
+            .start_millideg
+            .checked_add(FULL_TURN)
+            .is_some_and(|full| self.end_millideg >= full)
+        {
+            return true;
+        }
+        let span = (end + FULL_TURN - start) % FULL_TURN;
+        let (dx, dy) = point.offset_from(self.center);
+        (angle_millideg(dx, dy) + FULL_TURN - start) % FULL_TURN <= span
+    }
+}
+
+/// Clockwise screen angle of the offset `(dx, dy)` in millidegrees, `0..360_000`.
+///
+/// Reduces the offset to the first octant and approximates the arctangent there with
+/// `atan(t) ≈ π/4·t + t(1 - t)(0.2447 + 0.0663t)`, which is exact at 0° and 45°.
+fn angle_millideg(dx: i32, dy: i32) -> u32 {
+    let (ax, ay) = (i64::from(dx.unsigned_abs()), i64::from(dy.unsigned_abs()));
+    let (n, d) = if ay <= ax { (ay, ax) } else { (ax, ay) };
+    let octant = if d == 0 {
+        0
+    } else {
+        // 14020 and 3799 are the polynomial coefficients converted to millidegrees
+        45_000 * n / d + n * (d - n) * (14_020 * d + 3_799 * n) / (d * d * d)
+    };
+    let first_quadrant = if ay <= ax { octant } else { 90_000 - octant };
+    let angle = match (dx >= 0, dy >= 0) {
+        (true, true) => first_quadrant,
+        (false, true) => 180_000 - first_quadrant,
+        (false, false) => 180_000 + first_quadrant,
+        (true, false) => 360_000 - first_quadrant,
+    };
+    angle as u32
+}
+
+/// Any of the shapes in this module, so different shapes can share a [`HitMap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum Region {
+    /// See [`Circle`]
+    Circle(Circle),
+    /// See [`Rect`]
+    Rect(Rect),
+    /// See [`ArcSegment`]
+    Arc(ArcSegment),
+}
+
+impl Region {
+    /// Whether `point` is inside the shape.
+    pub fn contains(&self, point: &TouchPoint) -> bool {
+        match self {
+            Region::Circle(circle) => circle.contains(point),
+            Region::Rect(rect) => rect.contains(point),
+            Region::Arc(arc) => arc.contains(point),
+        }
+    }
+}
+
+impl From<Circle> for Region {
+    fn from(circle: Circle) -> Self {
+        Region::Circle(circle)
+    }
+}
+
+impl From<Rect> for Region {
+    fn from(rect: Rect) -> Self {
+        Region::Rect(rect)
+    }
+}
+
+impl From<ArcSegment> for Region {
+    fn from(arc: ArcSegment) -> Self {
+        Region::Arc(arc)
+    }
+}
+
+/// Maps touches to values, for example the buttons of a screen.
+///
+/// Regions may overlap, the first matching entry wins.
+#[derive(Debug, Clone, Copy)]
+pub struct HitMap<'a, T> {
+    entries: &'a [(Region, T)],
+}
+
+impl<'a, T> HitMap<'a, T> {
+    /// Create a map from `(region, value)` pairs, checked in order.
+    pub const fn new(entries: &'a [(Region, T)]) -> Self {
+        Self { entries }
+    }
+
+    /// The value of the first region containing `point`.
+    pub fn hit(&self, point: &TouchPoint) -> Option<&'a T> {
+        self.entries
+            .iter()
+            .find(|(region, _)| region.contains(point))
+            .map(|(_, value)| value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CENTER: TouchPoint = TouchPoint::new(120, 120);
+
+    fn at(dx: i32, dy: i32) -> TouchPoint {
+        TouchPoint::new((120 + dx) as u16, (120 + dy) as u16)
+    }
+
+    fn arc(start_millideg: u32, end_millideg: u32) -> ArcSegment {
+        ArcSegment {
+            center: CENTER,
+            inner_r: 50,
+            outer_r: 100,
+            start_millideg,
+            end_millideg,
+        }
+    }
+
+    #[test]
+    fn circle_edges() {
+        let circle = Circle {
+            center: CENTER,
+            radius: 5,
+        };
+        assert!(circle.contains(&CENTER));
+        assert!(circle.contains(&at(5, 0)));
+        assert!(circle.contains(&at(0, -5)));
+        assert!(circle.contains(&at(3, 4)));
+        assert!(!circle.contains(&at(6, 0)));
+        assert!(!circle.contains(&at(4, 4)));
+    }
+
+    #[test]
+    fn large_coordinates() {
+        let origin = TouchPoint::new(0, 0);
+        let corner = TouchPoint::new(u16::MAX, u16::MAX);
+        let circle = Circle {
+            center: origin,
+            radius: u16::MAX,
+        };
+        assert!(circle.contains(&TouchPoint::new(u16::MAX, 0)));
+        assert!(circle.contains(&TouchPoint::new(46_000, 46_000)));
+        assert!(!circle.contains(&corner));
+
+        let quarter = ArcSegment {
+            center: origin,
+            inner_r: 0,
+            outer_r: u16::MAX,
+            start_millideg: 0,
+            end_millideg: 90_000,
+        };
+        assert!(quarter.contains(&TouchPoint::new(0, u16::MAX)));
+        assert!(quarter.contains(&TouchPoint::new(46_000, 46_000)));
+        assert!(!quarter.contains(&corner));
+    }
+
+    #[test]
+    fn rect_edges() {
+        let rect = Rect {
+            min: TouchPoint::new(10, 20),
+            max: TouchPoint::new(30, 40),
+        };
+        for x in 0..50 {
+            for y in 0..50 {
+                let inside = (10..=30).contains(&x) && (20..=40).contains(&y);
+                assert_eq!(rect.contains(&TouchPoint::new(x, y)), inside, "({x}, {y})");
+            }
+        }
+    }
+
+    #[test]
+    fn angle_on_axes_and_diagonals() {
+        assert_eq!(angle_millideg(10, 0), 0);
+        assert_eq!(angle_millideg(10, 10), 45_000);
+        assert_eq!(angle_millideg(0, 10), 90_000);
+        assert_eq!(angle_millideg(-10, 10), 135_000);
+        assert_eq!(angle_millideg(-10, 0), 180_000);
+        assert_eq!(angle_millideg(-10, -10), 225_000);
+        assert_eq!(angle_millideg(0, -10), 270_000);
+        assert_eq!(angle_millideg(10, -10), 315_000);
+    }
+
+    #[test]
+    fn angle_matches_atan2() {
+        for dx in -100..=100 {
+            for dy in -100..=100 {
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+                let exact = f64::from(dy)
+                    .atan2(f64::from(dx))
+                    .to_degrees()
+                    .rem_euclid(360.0);
+                let approx = f64::from(angle_millideg(dx, dy)) / 1000.0;
+                let error = (exact - approx).abs();
+                assert!(error.min(360.0 - error) < 0.1, "({dx}, {dy})");
+            }
+        }
+    }
+
+    #[test]
+    fn arc_radius_edges() {
+        let right = arc(315_000, 45_000);
+        assert!(!right.contains(&at(49, 0)));
+        assert!(right.contains(&at(50, 0)));
+        assert!(right.contains(&at(100, 0)));
+        assert!(!right.contains(&at(101, 0)));
+        assert!(!right.contains(&CENTER));
+    }
+
+    #[test]
+    fn arc_across_seam() {
+        let right = arc(315_000, 45_000);
+        assert!(right.contains(&at(70, 0)));
+        assert!(right.contains(&at(60, 60)));
+        assert!(right.contains(&at(60, -60)));
+        assert!(right.contains(&at(70, 69)));
+        assert!(right.contains(&at(70, -69)));
+        assert!(!right.contains(&at(69, 70)));
+        assert!(!right.contains(&at(69, -70)));
+        assert!(!right.contains(&at(0, 70)));
+        assert!(!right.contains(&at(-70, 0)));
+        assert!(!right.contains(&at(0, -70)));
+    }
+
+    #[test]
+    fn arc_with_large_angles() {
+        // u32::MAX is 167.295° after whole turns, the start is 90° after whole turns
+        let down_left = arc(u32::MAX - 77_295, u32::MAX);
+        assert!(down_left.contains(&at(0, 70)));
+        assert!(down_left.contains(&at(-60, 20)));
+        assert!(!down_left.contains(&at(70, 0)));
+        assert!(!down_left.contains(&at(-70, -1)));
+
+        let full = arc(u32::MAX - FULL_TURN, u32::MAX);
+        assert!(full.contains(&at(70, 0)));
+        assert!(full.contains(&at(0, -70)));
+    }
+
+    #[test]
+    fn arc_ending_on_seam() {
+        let up_right = arc(270_000, 360_000);
+        assert!(up_right.contains(&at(0, -70)));
+        assert!(up_right.contains(&at(60, -60)));
+        assert!(up_right.contains(&at(70, 0)));
+        assert!(!up_right.contains(&at(70, 1)));
+        assert!(!up_right.contains(&at(-1, -70)));
+
+        let down_right = arc(0, 90_000);
+        assert!(down_right.contains(&at(70, 0)));
+        assert!(down_right.contains(&at(0, 70)));
+        assert!(!down_right.contains(&at(70, -1)));
+        assert!(!down_right.contains(&at(-1, 70)));
+    }
+
+    #[test]
+    fn arc_full_and_empty() {
+        let full = arc(0, 360_000);
+        let point = arc(90_000, 90_000);
+        for (dx, dy) in [(70, 0), (0, 70), (-70, 0), (0, -70), (50, -50)] {
+            assert!(full.contains(&at(dx, dy)));
+        }
+        assert!(point.contains(&at(0, 70)));
+        assert!(!point.contains(&at(1, 70)));
+        assert!(!point.contains(&at(-1, 70)));
+    }
+
+    #[test]
+    fn pie_slice_contains_center() {
+        let slice = ArcSegment {
+            inner_r: 0,
+            ..arc(0, 90_000)
+        };
+        assert!(slice.contains(&CENTER));
+    }
+
+    #[test]
+    fn hit_map_first_match_wins() {
+        let entries = [
+            (
+                Region::from(Circle {
+                    center: CENTER,
+                    radius: 20,
+                }),
+                "center",
+            ),
+            (arc(315_000, 45_000).into(), "right"),
+            (
+                Rect {
+                    min: TouchPoint::new(0, 0),
+                    max: TouchPoint::new(239, 239),
+                }
+                .into(),
+                "screen",
+            ),
+        ];
+        let map = HitMap::new(&entries);
+
+        assert_eq!(map.hit(&CENTER), Some(&"center"));
+        assert_eq!(map.hit(&at(80, 0)), Some(&"right"));
+        assert_eq!(map.hit(&at(-80, 0)), Some(&"screen"));
+        assert_eq!(map.hit(&TouchPoint::new(240, 0)), None);
+    }
+}
@@ -0,0 +1,138 @@
+//! # Hardware reset timing
+use embedded_hal::digital::{self, ErrorType, OutputPin, PinState};
+
+use crate::{Address, CST816S};
+
+/// Durations used when toggling the reset pin.
+///
+/// The reset sequence drives the reset pin high, pulls it low to hold the chip in reset,
+/// then releases it high again. The CST816S datasheet requires the reset line to be held low
+/// for at least 5ms, and the chip needs roughly 50ms after the line is released before it
+/// answers on the I2C bus. The [`Default`] impl follows those numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct ResetTimings {
+    /// Time to hold the line high before starting the reset pulse, in milliseconds.
+    pub pre_high_ms: u32,
+    /// Time to hold the line low, in milliseconds.
+    pub low_ms: u32,
+    /// Time to wait after releasing the line before the chip is usable, in milliseconds.
+    pub post_high_ms: u32,
+}
+
+impl Default for ResetTimings {
+    fn default() -> Self {
+        Self {
+            pre_high_ms: 50,
+            low_ms: 5,
+            post_high_ms: 50,
+        }
+    }
+}
+
+/// Which level of the reset pin holds the chip in reset.
+///
+/// The CST816S itself resets on a low level. Some carrier boards drive the line through an
+/// inverting transistor, so the pin has to be driven high to reset the chip instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum ResetPolarity {
+    /// A low level resets the chip, as on a direct connection
+    #[default]
+    ActiveLow,
+    /// A high level resets the chip, for inverted reset lines
+    ActiveHigh,
+}
+
+impl ResetPolarity {
+    /// Pin level that puts the chip in reset (`asserted`) or lets it run.
+    pub(crate) fn level(self, asserted: bool) -> PinState {
+        match self {
+            ResetPolarity::ActiveLow => PinState::from(!asserted),
+            ResetPolarity::ActiveHigh => PinState::from(asserted),
+        }
+    }
+}
+
+/// Placeholder for the reset pin on boards where the touch reset is hard-wired or shared with
+/// another device, for example the LCD reset line.
+///
+/// Every attempt to drive it fails with [`MissingResetPin`], so [`CST816S::reset`] fails
+/// without touching the chip. Configuration and event handling work as usual, but they cannot
+/// rely on a hardware reset having happened. Use [`CST816S::soft_reset`] to reset the chip
+/// through the interrupt pin instead.
+///
+/// [`Error`](crate::Error) reduces pin errors to their kind, so the reset fails with
+/// `Error::Pin(ErrorKind::Other)`, the same as a GPIO that reports an unspecified fault. The
+/// two are told apart by the pin type instead: a driver holding a `NoResetPin` fails every
+/// reset, one with a real pin only when the HAL reports a fault.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NoResetPin;
+
+/// Error returned when resetting a driver created with [`CST816S::new_no_reset`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct MissingResetPin;
+
+impl digital::Error for MissingResetPin {
+    fn kind(&self) -> digital::ErrorKind {
+        digital::ErrorKind::Other
+    }
+}
+
+impl ErrorType for NoResetPin {
+    type Error = MissingResetPin;
+}
+
+impl OutputPin for NoResetPin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        Err(MissingResetPin)
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        Err(MissingResetPin)
+    }
+}
+
+impl<I2C, TPINT> CST816S<I2C, TPINT, NoResetPin> {
+    /// Create a driver without a reset pin.
+    ///
+    /// See [`NoResetPin`] for what is available in this configuration.
+    pub fn new_no_reset(i2c: I2C, address: Address, interrupt_pin: TPINT) -> Self {
+        Self::new(i2c, address, interrupt_pin, NoResetPin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DEFAULT_ADDRESS, Error, IrqConfig, testing};
+    use embedded_hal_mock::eh1::{delay::CheckedDelay, digital, i2c};
+    use futures_test::test;
+
+    #[test]
+    async fn no_reset_driver_configures_without_reset() {
+        let mut i2c_device = i2c::Mock::new(&testing::write(0xFA, &[0b0100_0000]));
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut delay = CheckedDelay::new(&[]);
+        let mut touchpad = CST816S::new_no_reset(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin);
+
+        assert_eq!(
+            touchpad.reset(&mut delay),
+            Err(Error::Pin(embedded_hal::digital::ErrorKind::Other))
+        );
+        touchpad
+            .set_irq_config(IrqConfig {
+                once_wlp: false,
+                en_motion: false,
+                en_change: false,
+                en_touch: true,
+                en_test: false,
+            })
+            .unwrap();
+
+        i2c_device.done();
+        int_pin.done();
+        delay.done();
+    }
+}
@@ -0,0 +1,254 @@
+//! # Splitting the driver into a reader and a configuration half
+use crate::{
+    BoundsPolicy, CST816S, CoordinateWidth, Orientation,
+    device::{Device, DeviceInterface},
+};
+
+/// Placeholder for a pin that is owned by the other half of a split driver.
+///
+/// It implements neither `InputPin` nor `OutputPin`, so methods that need the missing pin are
+/// not available on that half.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Detached;
+
+/// Half of a split driver that owns the interrupt pin and reads touch reports.
+pub type TouchReader<I2C, TPINT> = CST816S<I2C, TPINT, Detached>;
+
+/// Half of a split driver that owns the reset pin and writes the configuration registers.
+pub type ConfigHandle<I2C, TPRST> = CST816S<I2C, Detached, TPRST>;
+
+impl<I2C, TPINT, TPRST> CST816S<I2C, TPINT, TPRST> {
+    /// Split the driver into a [`TouchReader`] and a [`ConfigHandle`].
+    ///
+    /// The reader keeps this driver's bus handle, the configuration half talks to the chip through
+    /// `config_i2c`. Both handles must point at the same physical bus, for example two
+    /// [`embedded-hal-bus`](https://crates.io/crates/embedded-hal-bus) devices like
+    /// `RefCellDevice` or `CriticalSectionDevice` created from one shared bus. This way the reader
+    /// can live in an interrupt task while configuration stays in the main task.
+    ///
+    /// The settings for decoding reports, like [`CST816S::set_panel_size`],
+    /// [`CST816S::set_orientation`], [`CST816S::set_bounds_policy`] and
+    /// [`CST816S::set_debounce`], live on the reader. The configuration half starts with the
+    /// defaults for them, and [`TouchReader::join`] keeps the reader's, so change them on the
+    /// reader only.
+    ///
+    /// The configuration written by [`CST816S::apply`] and the auto reapply setting live on the
+    /// configuration half, so write the configuration registers through it. The reader doesn't
+    /// track what it writes and never reapplies the configuration; check for chip resets with
+    /// [`CST816S::reapply_config_if_reset`] on the configuration half instead.
+    ///
+    /// Use [`TouchReader::join`] to get the original driver back.
+    pub fn split<CI2C>(
+        self,
+        config_i2c: CI2C,
+    ) -> (TouchReader<I2C, TPINT>, ConfigHandle<CI2C, TPRST>) {
+        let address = self.device.interface.device_address();
+        let reader = CST816S {
+            device: self.device,
+            interrupt_pin: self.interrupt_pin,
+            reset_pin: Detached,
+            held: self.held,
+            reset_polarity: self.reset_polarity,
+            variant: self.variant,
+            bpc_in_reports: self.bpc_in_reports,
+            last_event: self.last_event,
+            sequence: self.sequence,
+            reject_origin_glitch: self.reject_origin_glitch,
+            assume_ready_on_pin_error: self.assume_ready_on_pin_error,
+            auto_reapply: false,
+            reapply_pending: false,
+            panel_size: self.panel_size,
+            bounds_policy: self.bounds_policy,
+            orientation: self.orientation,
+            coordinate_width: self.coordinate_width,
+            rotate_gestures: self.rotate_gestures,
+            move_threshold: self.move_threshold,
+            debounce: self.debounce,
+            stable_point: self.stable_point,
+            last_point: self.last_point,
+            scroll_point: self.scroll_point,
+            applied_config: None,
+            muted_irq: None,
+        };
+        let config = CST816S {
+            device: Device::new(DeviceInterface::new(config_i2c, address)),
+            interrupt_pin: Detached,
+            reset_pin: self.reset_pin,
+            held: None,
+            reset_polarity: self.reset_polarity,
+            variant: self.variant,
+            bpc_in_reports: false,
+            last_event: None,
+            sequence: 0,
+            reject_origin_glitch: false,
+            assume_ready_on_pin_error: false,
+            auto_reapply: self.auto_reapply,
+            reapply_pending: self.reapply_pending,
+            panel_size: None,
+            bounds_policy: BoundsPolicy::PassThrough,
+            orientation: Orientation::Deg0,
+            coordinate_width: CoordinateWidth::Bits12,
+            rotate_gestures: false,
+            move_threshold: 0,
+            debounce: 0,
+            stable_point: None,
+            last_point: None,
+            scroll_point: None,
+            applied_config: self.applied_config,
+            muted_irq: self.muted_irq,
+        };
+        (reader, config)
+    }
+}
+
+impl<I2C, TPINT> TouchReader<I2C, TPINT> {
+    /// Reassemble a driver split with [`CST816S::split`].
+    ///
+    /// Returns the driver together with the bus handle that was used by the configuration half.
+    pub fn join<CI2C, TPRST>(
+        self,
+        config: ConfigHandle<CI2C, TPRST>,
+    ) -> (CST816S<I2C, TPINT, TPRST>, CI2C) {
+        let driver = CST816S {
+            device: self.device,
+            interrupt_pin: self.interrupt_pin,
+            reset_pin: config.reset_pin,
+            held: self.held,
+            reset_polarity: config.reset_polarity,
+            variant: self.variant,
+            bpc_in_reports: self.bpc_in_reports,
+            last_event: self.last_event,
+            sequence: self.sequence,
+            reject_origin_glitch: self.reject_origin_glitch,
+            assume_ready_on_pin_error: self.assume_ready_on_pin_error,
+            auto_reapply: config.auto_reapply,
+            reapply_pending: config.reapply_pending,
+            panel_size: self.panel_size,
+            bounds_policy: self.bounds_policy,
+            orientation: self.orientation,
+            coordinate_width: self.coordinate_width,
+            rotate_gestures: self.rotate_gestures,
+            move_threshold: self.move_threshold,
+            debounce: self.debounce,
+            stable_point: self.stable_point,
+            last_point: self.last_point,
+            scroll_point: self.scroll_point,
+            applied_config: config.applied_config,
+            muted_irq: config.muted_irq,
+        };
+        (driver, config.device.interface.release())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::RefCell;
+
+    use super::*;
+    use crate::{
+        DEFAULT_ADDRESS, IrqConfig, NoIntPin, NoResetPin, TouchPoint, mock::MockRegisterBank,
+        testing,
+    };
+    use embedded_hal_bus::i2c::RefCellDevice;
+    use embedded_hal_mock::eh1::{
+        digital::{self, State},
+        i2c,
+    };
+    use futures_test::test;
+
+    #[test]
+    async fn split_halves_share_bus() {
+        let mut expectations = testing::write(0xFA, &[0b0100_0000]);
+        expectations.extend(testing::report(0x05, 0x10, 0x20));
+        let bus = RefCell::new(i2c::Mock::new(&expectations));
+        let mut int_pin = digital::Mock::new(&[digital::Transaction::get(State::Low)]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let touchpad = CST816S::new(
+            RefCellDevice::new(&bus),
+            DEFAULT_ADDRESS,
+            &mut int_pin,
+            &mut rst_pin,
+        );
+
+        let (mut reader, mut config) = touchpad.split(RefCellDevice::new(&bus));
+        config
+            .set_irq_config(IrqConfig {
+                once_wlp: false,
+                en_motion: false,
+                en_change: false,
+                en_touch: true,
+                en_test: false,
+            })
+            .unwrap();
+        let event = reader.event().unwrap();
+        assert_eq!(event.point, TouchPoint::new(0x10, 0x20));
+
+        let _ = reader.join(config);
+        bus.borrow_mut().done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn auto_reapply_stays_with_config_half() {
+        let bank = RefCell::new(MockRegisterBank::new());
+        let mut touchpad = CST816S::new(
+            RefCellDevice::new(&bank),
+            DEFAULT_ADDRESS,
+            NoIntPin,
+            NoResetPin,
+        );
+        touchpad.init_config().unwrap();
+        touchpad.set_auto_reapply(true);
+        bank.borrow_mut()
+            .set(0x01, &[0x05, 1, 0x00, 0x10, 0x00, 0x20]);
+
+        let (mut reader, mut config) = touchpad.split(RefCellDevice::new(&bank));
+        // The chip reset itself, the reader does not notice
+        bank.borrow_mut().set(0xFA, &[0]);
+        assert!(reader.event().is_some());
+        assert_eq!(bank.borrow().get(0xFA), 0);
+        assert_eq!(config.reapply_config_if_reset(), Ok(true));
+        assert_eq!(bank.borrow().get(0xFA), 0b0111_0001);
+
+        let (mut touchpad, _) = reader.join(config);
+        bank.borrow_mut().set(0xFA, &[0]);
+        assert!(touchpad.event().is_some());
+        assert_eq!(bank.borrow().get(0xFA), 0b0111_0001);
+    }
+
+    #[test]
+    async fn report_settings_stay_with_reader() {
+        let bank = RefCell::new(MockRegisterBank::new());
+        let mut touchpad = CST816S::new(
+            RefCellDevice::new(&bank),
+            DEFAULT_ADDRESS,
+            NoIntPin,
+            NoResetPin,
+        );
+        touchpad.set_panel_size(240, 280);
+        touchpad.set_orientation(Orientation::Deg90);
+        touchpad.set_debounce(3);
+        touchpad.set_auto_reapply(true);
+
+        let (reader, mut config) = touchpad.split(RefCellDevice::new(&bank));
+        assert_eq!(
+            (reader.panel_size, reader.orientation, reader.debounce),
+            (Some((240, 280)), Orientation::Deg90, 3)
+        );
+        assert!(!reader.auto_reapply);
+        assert_eq!(
+            (config.panel_size, config.orientation, config.debounce),
+            (None, Orientation::Deg0, 0)
+        );
+        assert!(config.auto_reapply);
+
+        config.set_auto_reapply(false);
+        let (touchpad, _) = reader.join(config);
+        assert_eq!(
+            (touchpad.panel_size, touchpad.orientation, touchpad.debounce),
+            (Some((240, 280)), Orientation::Deg90, 3)
+        );
+        assert!(!touchpad.auto_reapply);
+    }
+}
@@ -0,0 +1,298 @@
+//! # Held touch state
+use embedded_hal::i2c::I2c;
+
+use crate::{
+    CST816S, Error, TouchEvent, TouchPoint,
+    device::{Gesture, field_sets::TouchData},
+    error::is_nack,
+};
+
+/// Result of [`CST816S::touch_state`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum TouchState {
+    /// A finger is on the panel, with the latest report
+    Pressed(TouchEvent),
+    /// The finger was lifted since the previous call
+    Released,
+    /// No finger on the panel, and none on the previous call either
+    Idle,
+}
+
+/// Result of [`CST816S::poll`], describing how the touch changed since the previous call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum TouchPhase {
+    /// A finger was put on the panel at this point
+    Down(TouchPoint),
+    /// The finger moved to this point
+    Move(TouchPoint),
+    /// The finger was lifted, this is the last point it was seen at
+    Up(TouchPoint),
+}
+
+impl<I2C, TPINT, TPRST> CST816S<I2C, TPINT, TPRST>
+where
+    I2C: I2c,
+{
+    /// Read whether a finger is held on the panel.
+    ///
+    /// Unlike [`CST816S::event`] this keeps reporting [`TouchState::Pressed`] for as long as the
+    /// finger stays down, so the application does not have to remember the last position itself.
+    /// The driver tracks the previous call to report [`TouchState::Released`] exactly once when
+    /// the finger is lifted.
+    ///
+    /// Release detection relies on the `FingerNum` register dropping to zero, so this reads the
+    /// registers on every call instead of waiting for the interrupt pin. A NACK from a chip that
+    /// went to sleep, or a report dropped by the [`crate::BoundsPolicy`], counts as no finger.
+    pub fn touch_state(&mut self) -> Result<TouchState, Error<I2C::Error>> {
+        let event = match self.read_finger()? {
+            Some(data) => self.decode_report(data)?,
+            None => None,
+        };
+        match event {
+            Some(event) => {
+                self.held = Some(event.point);
+                Ok(TouchState::Pressed(event))
+            }
+            None if self.held.take().is_some() => Ok(TouchState::Released),
+            None => Ok(TouchState::Idle),
+        }
+    }
+
+    /// Report press, move and release edges.
+    ///
+    /// Compares the current `FingerNum` and coordinates with the previous call and returns
+    /// `Ok(None)` when nothing changed. This reacts to a finger going down right away, without
+    /// waiting for the chip to classify a gesture. Like [`CST816S::touch_state`] it reads the
+    /// registers on every call, and both methods share the tracked finger position.
+    ///
+    /// [`TouchPhase::Move`] is only reported once the finger is at least
+    /// [`CST816S::set_move_threshold`] pixels away from the last reported point.
+    pub fn poll(&mut self) -> Result<Option<TouchPhase>, Error<I2C::Error>> {
+        let point = self
+            .read_finger()?
+            .and_then(|data| self.to_display(self.raw_point(data.x(), data.y())));
+        let phase = match (self.held, point) {
+            (None, Some(point)) => TouchPhase::Down(point),
+            (Some(last), Some(point))
+                if last.chebyshev_distance(point) >= self.move_threshold.max(1) =>
+            {
+                TouchPhase::Move(point)
+            }
+            (Some(last), None) => TouchPhase::Up(last),
+            _ => return Ok(None),
+        };
+        if point.is_some() {
+            self.last_point = point;
+        }
+        self.held = point;
+        Ok(Some(phase))
+    }
+
+    /// Only report [`TouchPhase::Move`] from [`CST816S::poll`] for moves of at least `px` pixels.
+    ///
+    /// The distance is the Chebyshev distance, the larger of the x and y differences, measured
+    /// from the last point that was reported. Smaller moves are swallowed, which hides the jitter
+    /// of a finger held still. Down and up edges, and the gestures from [`CST816S::event`], are
+    /// never filtered. The default of 0 reports every change.
+    pub fn set_move_threshold(&mut self, px: u16) {
+        self.move_threshold = px;
+    }
+
+    /// Report how far a continuous slide moved since the previous call, as `(dx, dy)` in pixels.
+    ///
+    /// With continuous slides enabled in the motion mask, see [`crate::MotionMaskConfig`], the
+    /// chip keeps reporting the slide gesture for as long as the finger moves. Calling this at a
+    /// steady rate during such a slide turns the reports into a scroll velocity for list UIs,
+    /// instead of one discrete swipe per contact. Without continuous slides the chip only
+    /// reports the gesture once the finger is lifted and this never returns a delta.
+    ///
+    /// Returns `Ok(None)` when no finger is down or the report is not a slide, and on the first
+    /// slide report of a contact, which only records the start point. Like
+    /// [`CST816S::poll`] it reads the registers on every call and follows the
+    /// [`crate::Orientation`].
+    pub fn scroll_delta(&mut self) -> Result<Option<(i16, i16)>, Error<I2C::Error>> {
+        let point = self.read_finger()?.and_then(|data| {
+            let slide = matches!(
+                data.gesture(),
+                Gesture::SlideUp | Gesture::SlideDown | Gesture::SlideLeft | Gesture::SlideRight
+            );
+            slide
+                .then(|| self.to_display(self.raw_point(data.x(), data.y())))
+                .flatten()
+        });
+        let delta = match (self.scroll_point, point) {
+            (Some(last), Some(point)) => {
+                let (dx, dy) = point.offset_from(last);
+                let clamp = |d: i32| d.clamp(i16::MIN.into(), i16::MAX.into()) as i16;
+                Some((clamp(dx), clamp(dy)))
+            }
+            _ => None,
+        };
+        self.scroll_point = point;
+        Ok(delta)
+    }
+
+    /// Read the touch registers, returning `None` when no finger is on the panel.
+    ///
+    /// A NACK from a chip that went to sleep counts as no finger.
+    fn read_finger(&mut self) -> Result<Option<TouchData>, Error<I2C::Error>> {
+        match self.read_touch_data() {
+            Ok(data) if data.finger_num() > 0 => Ok(Some(data)),
+            Ok(_) => Ok(None),
+            Err(e) if is_nack(&e.0) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DEFAULT_ADDRESS, testing};
+    use embedded_hal_mock::eh1::{digital, i2c};
+    use futures_test::test;
+
+    fn no_touch() -> i2c::Transaction {
+        i2c::Transaction::write_read(
+            testing::ADDRESS,
+            vec![0x01],
+            vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        )
+    }
+
+    fn touch(x: u16, y: u16) -> i2c::Transaction {
+        let [xh, xl] = x.to_be_bytes();
+        let [yh, yl] = y.to_be_bytes();
+        i2c::Transaction::write_read(
+            testing::ADDRESS,
+            vec![0x01],
+            vec![0x00, 0x01, xh, xl, yh, yl],
+        )
+    }
+
+    #[test]
+    async fn touch_state_tracks_release_edge() {
+        let mut expectations = vec![no_touch()];
+        expectations.extend(testing::report(0x00, 0x10, 0x20));
+        expectations.extend(testing::report(0x00, 0x11, 0x21));
+        expectations.push(no_touch());
+        expectations.push(no_touch());
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        assert_eq!(touchpad.touch_state().unwrap(), TouchState::Idle);
+        let TouchState::Pressed(event) = touchpad.touch_state().unwrap() else {
+            panic!("expected a press");
+        };
+        assert_eq!(event.point, TouchPoint::new(0x10, 0x20));
+        let TouchState::Pressed(event) = touchpad.touch_state().unwrap() else {
+            panic!("expected a held press");
+        };
+        assert_eq!(event.point, TouchPoint::new(0x11, 0x21));
+        assert_eq!(touchpad.touch_state().unwrap(), TouchState::Released);
+        assert_eq!(touchpad.touch_state().unwrap(), TouchState::Idle);
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn poll_reports_down_move_up() {
+        let mut i2c_device = i2c::Mock::new(&[
+            no_touch(),
+            touch(0x10, 0x20),
+            touch(0x10, 0x20),
+            touch(0x30, 0x40),
+            no_touch(),
+            no_touch(),
+        ]);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        assert_eq!(touchpad.poll().unwrap(), None);
+        assert_eq!(
+            touchpad.poll().unwrap(),
+            Some(TouchPhase::Down(TouchPoint::new(0x10, 0x20)))
+        );
+        assert_eq!(touchpad.poll().unwrap(), None);
+        assert_eq!(
+            touchpad.poll().unwrap(),
+            Some(TouchPhase::Move(TouchPoint::new(0x30, 0x40)))
+        );
+        assert_eq!(
+            touchpad.poll().unwrap(),
+            Some(TouchPhase::Up(TouchPoint::new(0x30, 0x40)))
+        );
+        assert_eq!(touchpad.poll().unwrap(), None);
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn scroll_delta_follows_continuous_slide() {
+        let mut expectations = testing::report(0x00, 100, 200);
+        expectations.extend(testing::report(0x01, 100, 180));
+        expectations.extend(testing::report(0x01, 102, 150));
+        expectations.extend(testing::report(0x01, 101, 140));
+        expectations.push(no_touch());
+        expectations.extend(testing::report(0x02, 50, 50));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        assert_eq!(touchpad.scroll_delta().unwrap(), None);
+        assert_eq!(touchpad.scroll_delta().unwrap(), None);
+        assert_eq!(touchpad.scroll_delta().unwrap(), Some((2, -30)));
+        assert_eq!(touchpad.scroll_delta().unwrap(), Some((-1, -10)));
+        assert_eq!(touchpad.scroll_delta().unwrap(), None);
+        assert_eq!(touchpad.scroll_delta().unwrap(), None);
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn poll_move_threshold_swallows_jitter() {
+        let mut i2c_device = i2c::Mock::new(&[
+            touch(100, 100),
+            touch(101, 99),
+            touch(102, 101),
+            touch(98, 100),
+            touch(103, 100),
+            touch(104, 101),
+            no_touch(),
+        ]);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+        touchpad.set_move_threshold(3);
+
+        let phases: Vec<_> = (0..7).filter_map(|_| touchpad.poll().unwrap()).collect();
+        assert_eq!(
+            phases,
+            [
+                TouchPhase::Down(TouchPoint::new(100, 100)),
+                TouchPhase::Move(TouchPoint::new(103, 100)),
+                TouchPhase::Up(TouchPoint::new(103, 100)),
+            ]
+        );
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+}
@@ -0,0 +1,26 @@
+//! Shared helpers for the mock based unit tests.
+use embedded_hal_mock::eh1::i2c::Transaction;
+
+/// Device address used by all tests.
+pub(crate) const ADDRESS: u8 = 0x15;
+
+/// Transactions produced by reading a full touch report.
+pub(crate) fn report(gesture: u8, x: u16, y: u16) -> Vec<Transaction> {
+    let [xh, xl] = x.to_be_bytes();
+    let [yh, yl] = y.to_be_bytes();
+    vec![Transaction::write_read(
+        ADDRESS,
+        vec![0x01],
+        vec![gesture, 1, xh, xl, yh, yl],
+    )]
+}
+
+/// Transactions produced by writing `data` to the register at `register`.
+pub(crate) fn write(register: u8, data: &[u8]) -> Vec<Transaction> {
+    vec![
+        Transaction::transaction_start(ADDRESS),
+        Transaction::write(ADDRESS, vec![register]),
+        Transaction::write(ADDRESS, data.to_vec()),
+        Transaction::transaction_end(ADDRESS),
+    ]
+}
@@ -0,0 +1,322 @@
+//! # Custom register transports
+//!
+//! The driver is written against [`I2c`](i2c::I2c): every method is bound on it and returns
+//! [`Error<I2C::Error>`](crate::Error). Making the transport a type parameter of its own would
+//! change the type of every driver and error in user code, so other transports plug in through
+//! [`RegisterBus`] instead, which turns the driver's I2C transactions back into register
+//! accesses.
+use device_driver::RegisterInterface;
+use embedded_hal::i2c::{self, ErrorKind, NoAcknowledgeSource, Operation, SevenBitAddress};
+
+use crate::{Address, CST816S, DEFAULT_ADDRESS};
+
+/// Adapter that lets [`CST816S`] talk to the chip through any
+/// [`RegisterInterface`] instead of an I2C bus.
+///
+/// Useful for a bridge that reaches the chip over another link, or a shim that records every
+/// register access. Like the chip, the bus answers at a single address and NACKs every other
+/// one, so [`CST816S::scan`] works as on a real bus. The first byte written in a transaction
+/// selects the register, every further byte read or written moves on to the next one, and a
+/// read without a register byte continues where the previous transfer stopped. Every byte is
+/// passed to the [`RegisterInterface`] as a call of its own, see [`CST816S::from_interface`].
+#[derive(Debug)]
+pub struct RegisterBus<R> {
+    interface: R,
+    address: Address,
+    pointer: u8,
+}
+
+impl<R> RegisterBus<R> {
+    /// Wrap `interface`, answering at [`DEFAULT_ADDRESS`].
+    pub const fn new(interface: R) -> Self {
+        Self::with_address(interface, DEFAULT_ADDRESS)
+    }
+
+    /// Wrap `interface`, answering at `address`.
+    pub const fn with_address(interface: R, address: Address) -> Self {
+        Self {
+            interface,
+            address,
+            pointer: 0,
+        }
+    }
+
+    /// Give back the wrapped interface.
+    pub fn into_inner(self) -> R {
+        self.interface
+    }
+}
+
+/// Error of a [`RegisterBus`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum RegisterBusError<E> {
+    /// The transaction was addressed to another device, reported as a NACK of the address
+    NoAcknowledge,
+    /// The [`RegisterInterface`] failed
+    ///
+    /// Its errors carry no [`ErrorKind`], so they are reported as [`ErrorKind::Other`] and never
+    /// taken for a NACK from a sleeping chip. Match on this variant to get the cause.
+    Interface(E),
+}
+
+impl<E: core::fmt::Debug> i2c::Error for RegisterBusError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::NoAcknowledge => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address),
+            Self::Interface(_) => ErrorKind::Other,
+        }
+    }
+}
+
+impl<R> i2c::ErrorType for RegisterBus<R>
+where
+    R: RegisterInterface<AddressType = u8>,
+    R::Error: core::fmt::Debug,
+{
+    type Error = RegisterBusError<R::Error>;
+}
+
+impl<R> i2c::I2c for RegisterBus<R>
+where
+    R: RegisterInterface<AddressType = u8>,
+    R::Error: core::fmt::Debug,
+{
+    fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        if address != self.address.get() {
+            return Err(RegisterBusError::NoAcknowledge);
+        }
+        let mut selected = false;
+        for operation in operations {
+            match operation {
+                Operation::Write(bytes) => {
+                    let mut data = &bytes[..];
+                    if !selected && let Some((&register, rest)) = data.split_first() {
+                        self.pointer = register;
+                        selected = true;
+                        data = rest;
+                    }
+                    for byte in data.chunks(1) {
+                        self.interface
+                            .write_register(self.pointer, 8, byte)
+                            .map_err(RegisterBusError::Interface)?;
+                        self.pointer = self.pointer.wrapping_add(1);
+                    }
+                }
+                Operation::Read(buffer) => {
+                    for byte in buffer.chunks_mut(1) {
+                        self.interface
+                            .read_register(self.pointer, 8, byte)
+                            .map_err(RegisterBusError::Interface)?;
+                        self.pointer = self.pointer.wrapping_add(1);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R, TPINT, TPRST> CST816S<RegisterBus<R>, TPINT, TPRST> {
+    /// Create a driver on top of a custom register transport, see [`RegisterBus`].
+    ///
+    /// `interface` must implement [`RegisterInterface`] with `u8` register addresses, the
+    /// driver then works as with an I2C bus. [`CST816S::release`] gives back the
+    /// [`RegisterBus`], [`RegisterBus::into_inner`] the interface.
+    ///
+    /// Every call to `interface` accesses a single register: `size_bits` is always 8 and the
+    /// data one byte long, as every register of the chip is one byte. Transfers the driver
+    /// does in one go, like the six byte touch report or the 19 byte
+    /// [configuration block](CST816S::dump_config), arrive as one call per register at
+    /// consecutive addresses. The wider registers of the register map, like `TouchData` or
+    /// `Xpos`, only combine these for the driver and are never passed to `interface`.
+    pub fn from_interface(interface: R, interrupt_pin: TPINT, reset_pin: TPRST) -> Self {
+        Self::new(
+            RegisterBus::new(interface),
+            DEFAULT_ADDRESS,
+            interrupt_pin,
+            reset_pin,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_hal::i2c::{Error as _, I2c as _};
+
+    use crate::{
+        IrqConfig, KNOWN_ADDRESSES, NoIntPin, NoResetPin, TouchPoint, device::DeviceError,
+        mock::MockRegisterBank,
+    };
+
+    /// Interface failure of a [`Recorder`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Broken;
+
+    /// Logs the register accesses of the wrapped bank as `(register, write)`, checking that
+    /// each accesses one byte.
+    struct Recorder {
+        bank: MockRegisterBank,
+        log: Vec<(u8, bool)>,
+        broken: bool,
+    }
+
+    impl Recorder {
+        fn new(bank: MockRegisterBank) -> Self {
+            Self {
+                bank,
+                log: Vec::new(),
+                broken: false,
+            }
+        }
+    }
+
+    impl RegisterInterface for Recorder {
+        type Error = Broken;
+
+        type AddressType = u8;
+
+        fn write_register(
+            &mut self,
+            address: u8,
+            size_bits: u32,
+            data: &[u8],
+        ) -> Result<(), Self::Error> {
+            if self.broken {
+                return Err(Broken);
+            }
+            assert_eq!((size_bits, data.len()), (8, 1));
+            self.log.push((address, true));
+            self.bank.set(address, data);
+            Ok(())
+        }
+
+        fn read_register(
+            &mut self,
+            address: u8,
+            size_bits: u32,
+            data: &mut [u8],
+        ) -> Result<(), Self::Error> {
+            if self.broken {
+                return Err(Broken);
+            }
+            assert_eq!((size_bits, data.len()), (8, 1));
+            self.log.push((address, false));
+            data[0] = self.bank.get(address);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn driver_over_custom_interface() {
+        let mut bank = MockRegisterBank::new();
+        bank.set(0x01, &[0x05, 1, 0x00, 0x10, 0x00, 0x20]);
+        let mut touchpad = CST816S::from_interface(Recorder::new(bank), NoIntPin, NoResetPin);
+
+        let event = touchpad.event().unwrap();
+        assert_eq!(event.point, TouchPoint::new(0x10, 0x20));
+        touchpad
+            .set_irq_config(IrqConfig::touch_tracking())
+            .unwrap();
+
+        let (bus, _, _) = touchpad.release();
+        let recorder = bus.into_inner();
+        assert_eq!(recorder.bank.get(0xFA), 0x60);
+        // `FingerNum` for the pending check, the report, then the `IrqCtl` write
+        let mut expected = vec![(0x02, false)];
+        expected.extend((0x01..=0x06).map(|register| (register, false)));
+        expected.push((0xFA, true));
+        assert_eq!(recorder.log, expected);
+    }
+
+    #[test]
+    fn multi_operation_transaction() {
+        let mut bank = MockRegisterBank::new();
+        bank.set(0xFC, &[0x0A, 0x0B]);
+        let mut bus = RegisterBus::new(Recorder::new(bank));
+
+        let mut read = [0; 2];
+        bus.transaction(
+            DEFAULT_ADDRESS.get(),
+            &mut [
+                Operation::Write(&[0xFA]),
+                Operation::Write(&[0x60, 0x01]),
+                Operation::Read(&mut read),
+            ],
+        )
+        .unwrap();
+        assert_eq!(read, [0x0A, 0x0B]);
+
+        // Without a register byte the read continues after the last transfer
+        let mut next = [0];
+        bus.read(DEFAULT_ADDRESS.get(), &mut next).unwrap();
+
+        let recorder = bus.into_inner();
+        assert_eq!(
+            (recorder.bank.get(0xFA), recorder.bank.get(0xFB)),
+            (0x60, 0x01)
+        );
+        assert_eq!(
+            recorder.log,
+            [
+                (0xFA, true),
+                (0xFB, true),
+                (0xFC, false),
+                (0xFD, false),
+                (0xFE, false)
+            ]
+        );
+    }
+
+    #[test]
+    fn config_block_read_per_register() {
+        let mut touchpad =
+            CST816S::from_interface(Recorder::new(MockRegisterBank::new()), NoIntPin, NoResetPin);
+        let mut block = [0; 19];
+        touchpad.dump_config(&mut block).unwrap();
+
+        let (bus, _, _) = touchpad.release();
+        let expected: Vec<_> = (0xEC..=0xFE).map(|register| (register, false)).collect();
+        assert_eq!(bus.into_inner().log, expected);
+    }
+
+    #[test]
+    fn answers_only_at_its_address() {
+        let mut bus =
+            RegisterBus::with_address(Recorder::new(MockRegisterBank::new()), KNOWN_ADDRESSES[1]);
+
+        let error = bus.write(DEFAULT_ADDRESS.get(), &[0xFA, 0x60]).unwrap_err();
+        assert_eq!(error, RegisterBusError::NoAcknowledge);
+        assert_eq!(
+            error.kind(),
+            ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)
+        );
+        assert_eq!(
+            CST816S::scan(&mut bus, &KNOWN_ADDRESSES),
+            Ok(Some(KNOWN_ADDRESSES[1]))
+        );
+        assert_eq!(bus.into_inner().bank.get(0xFA), 0);
+    }
+
+    #[test]
+    fn interface_errors_propagate() {
+        let mut recorder = Recorder::new(MockRegisterBank::new());
+        recorder.broken = true;
+        let mut touchpad = CST816S::from_interface(recorder, NoIntPin, NoResetPin);
+
+        assert_eq!(
+            touchpad.irq_config(),
+            Err(DeviceError(RegisterBusError::Interface(Broken)))
+        );
+        assert_eq!(
+            touchpad.read_config(),
+            Err(crate::Error::I2c(RegisterBusError::Interface(Broken)))
+        );
+        assert_eq!(RegisterBusError::Interface(Broken).kind(), ErrorKind::Other);
+    }
+}
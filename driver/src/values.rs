@@ -0,0 +1,298 @@
+//! # Range checked register values
+//!
+//! Newtypes for the configuration registers that only accept part of the `u8` range. Each one
+//! is created with `try_new`, which returns `None` out of range, derefs to the raw register
+//! value and defaults to the reset value from the datasheet. The register map reads them with
+//! `TryFrom<u8>`, so a register holding an out of range value reads as [`ValueOutOfRange`].
+use core::ops::Deref;
+
+/// A raw register value outside the range of its newtype.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct ValueOutOfRange(pub u8);
+
+impl core::fmt::Display for ValueOutOfRange {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "register value {} out of range", self.0)
+    }
+}
+
+macro_rules! bounded_u8 {
+    ($(#[$meta:meta])* $name:ident, $range:pat, default: $default:literal) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+        #[cfg_attr(
+            feature = "serde",
+            derive(serde::Serialize, serde::Deserialize),
+            serde(try_from = "u8", into = "u8")
+        )]
+        pub struct $name(u8);
+
+        impl $name {
+            /// Returns `None` if `value` is outside the range of the register.
+            pub const fn try_new(value: u8) -> Option<Self> {
+                if matches!(value, $range) {
+                    Some(Self(value))
+                } else {
+                    None
+                }
+            }
+
+            /// The raw register value.
+            pub const fn get(self) -> u8 {
+                self.0
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self($default)
+            }
+        }
+
+        impl Deref for $name {
+            type Target = u8;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl From<$name> for u8 {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl TryFrom<u8> for $name {
+            type Error = ValueOutOfRange;
+
+            fn try_from(value: u8) -> Result<Self, Self::Error> {
+                Self::try_new(value).ok_or(ValueOutOfRange(value))
+            }
+        }
+    };
+}
+
+bounded_u8! {
+    /// Interrupt pulse width for the `IrqPulseWidth` register, in 0.1ms steps.
+    ///
+    /// Range: 1-200, default: 10
+    PulseWidth, 1..=200, default: 10
+}
+
+impl PulseWidth {
+    /// Create a new `PulseWidth` instance. Asserts that the value is in the range 1-200,
+    /// as required by the chip documentation
+    ///
+    /// The check only runs in debug builds, use [`PulseWidth::try_new`] or `TryFrom` for
+    /// values that are not known to be valid.
+    pub fn new(value: u8) -> Self {
+        debug_assert!(value > 0);
+        debug_assert!(value <= 200);
+        Self(value)
+    }
+
+    /// Round `us` down to a multiple of 100us.
+    ///
+    /// Fails outside of 100..=20000us, the error holds the rounded step count, saturated at
+    /// 255.
+    pub const fn from_us(us: u16) -> Result<Self, ValueOutOfRange> {
+        let steps = us / 100;
+        if steps > u8::MAX as u16 {
+            return Err(ValueOutOfRange(u8::MAX));
+        }
+        match Self::try_new(steps as u8) {
+            Some(pulse_width) => Ok(pulse_width),
+            None => Err(ValueOutOfRange(steps as u8)),
+        }
+    }
+
+    /// The pulse width in microseconds.
+    pub const fn as_us(self) -> u16 {
+        self.0 as u16 * 100
+    }
+}
+
+bounded_u8! {
+    /// Normal scan period for the `NorScanPer` register, in steps of 10ms.
+    ///
+    /// Range: 1-30 steps, 10-300ms, default: 1. The period also scales the `LpAutoWakeTime` and
+    /// `AutoSleepTime` timings.
+    ScanPeriod, 1..=30, default: 1
+}
+
+impl ScanPeriod {
+    /// Round `ms` down to a multiple of 10ms, returning `None` outside of 10..=300ms.
+    pub const fn from_ms(ms: u16) -> Option<Self> {
+        if ms >= 10 && ms <= 300 {
+            Some(Self((ms / 10) as u8))
+        } else {
+            None
+        }
+    }
+
+    /// The number of 10ms steps.
+    pub const fn steps(self) -> u8 {
+        self.0
+    }
+
+    /// The period in milliseconds.
+    pub const fn as_ms(self) -> u16 {
+        self.0 as u16 * 10
+    }
+}
+
+bounded_u8! {
+    /// Minutes between recalibrations in low power mode, for the `LpAutoWakeTime` register.
+    ///
+    /// Range: 1-5, default: 5
+    WakeMinutes, 1..=5, default: 5
+}
+
+bounded_u8! {
+    /// Wake-up threshold for the `LpScanTH` register, smaller is more sensitive.
+    ///
+    /// Range: 1-255, default: 48
+    LpScanThreshold, 1..=255, default: 48
+}
+
+bounded_u8! {
+    /// Scanning range for the `LpScanWin` register, larger is more sensitive and draws more
+    /// current.
+    ///
+    /// Range: 0-3, default: 3
+    LpScanWindow, 0..=3, default: 3
+}
+
+bounded_u8! {
+    /// Scanning frequency for the `LpScanFreq` register, smaller is more sensitive.
+    ///
+    /// Range: 1-255, default: 7
+    LpScanFrequency, 1..=255, default: 7
+}
+
+bounded_u8! {
+    /// Scanning current for the `LpScanIdac` register, smaller is more sensitive.
+    ///
+    /// Range: 1-255. The datasheet gives no reset value, the default of 1 is the most
+    /// sensitive setting.
+    LpScanCurrent, 1..=255, default: 1
+}
+
+bounded_u8! {
+    /// Seconds without touch before the chip enters low power mode, for the `AutoSleepTime`
+    /// register.
+    ///
+    /// Range: 1-255, default: 2
+    SleepSeconds, 1..=255, default: 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boundaries() {
+        type Check = fn(u8) -> Option<u8>;
+        let cases: [(&str, Check, u8, u8, u8); 8] = [
+            (
+                "PulseWidth",
+                |v| PulseWidth::try_new(v).map(u8::from),
+                1,
+                200,
+                *PulseWidth::default(),
+            ),
+            (
+                "ScanPeriod",
+                |v| ScanPeriod::try_new(v).map(u8::from),
+                1,
+                30,
+                *ScanPeriod::default(),
+            ),
+            (
+                "WakeMinutes",
+                |v| WakeMinutes::try_new(v).map(u8::from),
+                1,
+                5,
+                *WakeMinutes::default(),
+            ),
+            (
+                "LpScanThreshold",
+                |v| LpScanThreshold::try_new(v).map(u8::from),
+                1,
+                255,
+                *LpScanThreshold::default(),
+            ),
+            (
+                "LpScanWindow",
+                |v| LpScanWindow::try_new(v).map(u8::from),
+                0,
+                3,
+                *LpScanWindow::default(),
+            ),
+            (
+                "LpScanFrequency",
+                |v| LpScanFrequency::try_new(v).map(u8::from),
+                1,
+                255,
+                *LpScanFrequency::default(),
+            ),
+            (
+                "LpScanCurrent",
+                |v| LpScanCurrent::try_new(v).map(u8::from),
+                1,
+                255,
+                *LpScanCurrent::default(),
+            ),
+            (
+                "SleepSeconds",
+                |v| SleepSeconds::try_new(v).map(u8::from),
+                1,
+                255,
+                *SleepSeconds::default(),
+            ),
+        ];
+        let expected_defaults = [10, 1, 5, 48, 3, 7, 1, 2];
+
+        for ((name, check, min, max, default), expected) in cases.into_iter().zip(expected_defaults)
+        {
+            for value in 0..=255 {
+                let in_range = (min..=max).contains(&value);
+                assert_eq!(check(value), in_range.then_some(value), "{name}: {value}");
+            }
+            assert_eq!(default, expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn pulse_width_try_from() {
+        assert_eq!(PulseWidth::try_from(0), Err(ValueOutOfRange(0)));
+        assert_eq!(PulseWidth::try_from(1).map(u8::from), Ok(1));
+        assert_eq!(PulseWidth::try_from(200).map(u8::from), Ok(200));
+        assert_eq!(PulseWidth::try_from(201), Err(ValueOutOfRange(201)));
+    }
+
+    #[test]
+    fn pulse_width_us() {
+        for steps in 1..=200u8 {
+            let pulse_width = PulseWidth::try_new(steps).unwrap();
+            assert_eq!(PulseWidth::from_us(pulse_width.as_us()), Ok(pulse_width));
+            assert_eq!(
+                PulseWidth::from_us(pulse_width.as_us() + 99),
+                Ok(pulse_width)
+            );
+        }
+        assert_eq!(PulseWidth::default().as_us(), 1000);
+        assert_eq!(PulseWidth::from_us(99), Err(ValueOutOfRange(0)));
+        assert_eq!(PulseWidth::from_us(20100), Err(ValueOutOfRange(201)));
+        assert_eq!(PulseWidth::from_us(u16::MAX), Err(ValueOutOfRange(255)));
+    }
+
+    #[test]
+    fn try_from_reports_value() {
+        assert_eq!(WakeMinutes::try_from(6), Err(ValueOutOfRange(6)));
+        assert_eq!(LpScanWindow::try_from(3).map(u8::from), Ok(3));
+    }
+}
@@ -0,0 +1,206 @@
+//! # Chip variants
+use embedded_hal::i2c::I2c;
+
+use crate::{CST816S, Error};
+
+/// Members of the CST816 family that share the CST816S register map.
+///
+/// The variants report a different `ChipId` (0xA7). The register map of this crate follows the
+/// CST816S datasheet; the T and D parts leave out some of it, which [`CST816S::init_config`]
+/// takes into account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum ChipVariant {
+    /// CST816S, `ChipId` 0xB4
+    #[default]
+    Cst816S,
+    /// CST816T, `ChipId` 0xB5
+    Cst816T,
+    /// CST816D, `ChipId` 0xB6
+    Cst816D,
+}
+
+/// `ChipId` values of the variants in [`ChipVariant`].
+pub const KNOWN_CHIP_IDS: [u8; 3] = [0xB4, 0xB5, 0xB6];
+
+impl ChipVariant {
+    /// Value of the `ChipId` register reported by this variant.
+    pub const fn chip_id(self) -> u8 {
+        match self {
+            ChipVariant::Cst816S => 0xB4,
+            ChipVariant::Cst816T => 0xB5,
+            ChipVariant::Cst816D => 0xB6,
+        }
+    }
+
+    /// Look up the variant reporting `chip_id`.
+    pub const fn from_chip_id(chip_id: u8) -> Option<Self> {
+        match chip_id {
+            0xB4 => Some(ChipVariant::Cst816S),
+            0xB5 => Some(ChipVariant::Cst816T),
+            0xB6 => Some(ChipVariant::Cst816D),
+            _ => None,
+        }
+    }
+
+    /// Whether the `MotionMask` register (double click and continuous slides) is available.
+    ///
+    /// It is only documented for the CST816S.
+    pub const fn has_motion_mask(self) -> bool {
+        matches!(self, ChipVariant::Cst816S)
+    }
+}
+
+/// Identification registers of a chip, read with [`CST816S::firmware_info`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct FirmwareInfo {
+    /// Variant matching the `ChipId` register
+    pub variant: ChipVariant,
+    /// Contents of the `ProjId` register
+    pub project_id: u8,
+    /// Contents of the `FwVersion` register
+    pub fw_version: u8,
+}
+
+impl<I2C, TPINT, TPRST> CST816S<I2C, TPINT, TPRST> {
+    /// The chip variant the driver assumes, [`ChipVariant::Cst816S`] unless changed.
+    pub fn variant(&self) -> ChipVariant {
+        self.variant
+    }
+
+    /// Tell the driver which chip variant is fitted, for modules where it is known up front.
+    pub fn set_variant(&mut self, variant: ChipVariant) {
+        self.variant = variant;
+    }
+}
+
+impl<I2C, TPINT, TPRST> CST816S<I2C, TPINT, TPRST>
+where
+    I2C: I2c,
+{
+    /// Check that a CST816 is answering on the bus, before [`CST816S::init_config`] writes to it.
+    ///
+    /// Reads the `ChipId` register and returns the variant it belongs to, without changing the
+    /// variant the driver assumes. Modules seen in the field report 0xB4 (CST816S), 0xB5 (CST816T)
+    /// and 0xB6 (CST816D). Anything else, like the 0x00 or 0xFF of a mis-wired bus or another chip
+    /// answering at the address, returns [`Error::WrongChipId`] with the value read. A missing
+    /// chip shows up as an I2C error. The chip must be awake, so call this right after a reset.
+    pub fn probe(&mut self) -> Result<ChipVariant, Error<I2C::Error>> {
+        let chip_id = self.read_chip_id_now()?;
+        ChipVariant::from_chip_id(chip_id).ok_or(Error::WrongChipId {
+            expected: &KNOWN_CHIP_IDS,
+            found: chip_id,
+        })
+    }
+
+    /// Read the raw `ChipId` register, without looking at the interrupt pin or checking the
+    /// value like [`CST816S::probe`] does.
+    pub fn read_chip_id_now(&mut self) -> Result<u8, Error<I2C::Error>> {
+        Ok(self.device.chip_id().read()?.value())
+    }
+
+    /// Read the `ChipId`, `ProjId` and `FwVersion` registers.
+    ///
+    /// Returns [`Error::WrongChipId`] like [`CST816S::probe`]. Does not change the variant the
+    /// driver assumes.
+    pub fn firmware_info(&mut self) -> Result<FirmwareInfo, Error<I2C::Error>> {
+        let variant = self.probe()?;
+        let project_id = self.device.proj_id().read()?.value();
+        let fw_version = self.device.fw_version().read()?.value();
+        Ok(FirmwareInfo {
+            variant,
+            project_id,
+            fw_version,
+        })
+    }
+
+    /// Read the `ChipId` register and switch the driver to the matching variant.
+    ///
+    /// Returns [`Error::WrongChipId`] like [`CST816S::probe`], leaving the current variant
+    /// unchanged.
+    pub fn detect_variant(&mut self) -> Result<ChipVariant, Error<I2C::Error>> {
+        let variant = self.probe()?;
+        self.variant = variant;
+        Ok(variant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DEFAULT_ADDRESS, testing};
+    use embedded_hal_mock::eh1::{digital, i2c};
+    use futures_test::test;
+
+    fn chip_id(value: u8) -> i2c::Transaction {
+        i2c::Transaction::write_read(testing::ADDRESS, vec![0xA7], vec![value])
+    }
+
+    #[test]
+    async fn detect_variant_reads_chip_id() {
+        let mut i2c_device = i2c::Mock::new(&[chip_id(0xB5), chip_id(0x42)]);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        assert_eq!(touchpad.detect_variant().unwrap(), ChipVariant::Cst816T);
+        assert_eq!(
+            touchpad.detect_variant(),
+            Err(Error::WrongChipId {
+                expected: &KNOWN_CHIP_IDS,
+                found: 0x42
+            })
+        );
+        assert_eq!(touchpad.variant(), ChipVariant::Cst816T);
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn probe_rejects_unknown_chip() {
+        let mut i2c_device = i2c::Mock::new(&[chip_id(0xFF), chip_id(0xB6)]);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        assert_eq!(
+            touchpad.probe(),
+            Err(Error::WrongChipId {
+                expected: &KNOWN_CHIP_IDS,
+                found: 0xFF
+            })
+        );
+        assert_eq!(touchpad.probe(), Ok(ChipVariant::Cst816D));
+        assert_eq!(touchpad.variant(), ChipVariant::Cst816S);
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+
+    #[test]
+    async fn init_config_skips_motion_mask_without_support() {
+        let mut expectations = Vec::new();
+        expectations.extend(testing::write(0xFA, &[0b0111_0001]));
+        expectations.extend(testing::write(0xFE, &[0xFE]));
+        expectations.extend(testing::write(0xED, &[0x01]));
+        expectations.extend(testing::write(0xEE, &[0x01]));
+        let mut i2c_device = i2c::Mock::new(&expectations);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        touchpad.set_variant(ChipVariant::Cst816D);
+        touchpad.init_config().unwrap();
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+    }
+}
@@ -0,0 +1,314 @@
+//! # Blocking wait helpers
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+
+use crate::{CST816S, Error, TouchEvent, TouchReady, error::is_nack};
+
+/// Default interval between two checks of the interrupt pin in [`CST816S::wait_for_touch`].
+pub const DEFAULT_POLL_INTERVAL_MS: u32 = 5;
+
+impl<I2C, TPINT, TPRST> CST816S<I2C, TPINT, TPRST>
+where
+    I2C: I2c,
+    TPINT: TouchReady,
+{
+    /// Block until the panel is touched or `timeout_ms` elapses.
+    ///
+    /// Checks the interrupt pin every [`DEFAULT_POLL_INTERVAL_MS`], see
+    /// [`CST816S::wait_for_touch_with_interval`] for the timing details. Returns `Ok(None)` on
+    /// timeout. The report is acknowledged, like with [`CST816S::event`].
+    #[doc(alias = "wait_for_event")]
+    pub fn wait_for_touch(
+        &mut self,
+        delay: &mut impl DelayNs,
+        timeout_ms: u32,
+    ) -> Result<Option<TouchEvent>, Error<I2C::Error>> {
+        self.wait_for_touch_with_interval(delay, timeout_ms, DEFAULT_POLL_INTERVAL_MS)
+    }
+
+    /// Block until the panel is touched or `timeout_ms` elapses, checking every `poll_interval_ms`.
+    ///
+    /// The interrupt pin is checked right away and then after every `poll_interval_ms`. The
+    /// timeout is inclusive: the last check happens exactly `timeout_ms` after the first one, so a
+    /// touch arriving at the deadline is still reported. Returns `Ok(None)` on timeout.
+    ///
+    /// Time spent on the I2C bus is not counted, so the real timeout is slightly longer.
+    pub fn wait_for_touch_with_interval(
+        &mut self,
+        delay: &mut impl DelayNs,
+        timeout_ms: u32,
+        poll_interval_ms: u32,
+    ) -> Result<Option<TouchEvent>, Error<I2C::Error>> {
+        self.poll_until(delay, timeout_ms, poll_interval_ms, |driver| {
+            if driver.interrupt_pending()?
+                && let Some(event) = driver.read_report()?
+            {
+                driver.acknowledge();
+                return Ok(Some(event));
+            }
+            Ok(None)
+        })
+    }
+}
+
+impl<I2C, TPINT, TPRST> CST816S<I2C, TPINT, TPRST>
+where
+    I2C: I2c,
+{
+    /// Run `check` right away and then every `interval_ms` until it returns `Some`, or return
+    /// `Ok(None)` once `timeout_ms` elapsed. The last check happens exactly at the timeout.
+    fn poll_until<T>(
+        &mut self,
+        delay: &mut impl DelayNs,
+        timeout_ms: u32,
+        interval_ms: u32,
+        mut check: impl FnMut(&mut Self) -> Result<Option<T>, Error<I2C::Error>>,
+    ) -> Result<Option<T>, Error<I2C::Error>> {
+        let interval_ms = interval_ms.max(1);
+        let mut elapsed = 0;
+        loop {
+            if let Some(value) = check(self)? {
+                return Ok(Some(value));
+            }
+            if elapsed >= timeout_ms {
+                return Ok(None);
+            }
+            let step = interval_ms.min(timeout_ms - elapsed);
+            delay.delay_ms(step);
+            elapsed += step;
+        }
+    }
+
+    /// Block until no finger is on the panel or `timeout_ms` elapses.
+    ///
+    /// Polls the `FingerNum` register every [`DEFAULT_POLL_INTERVAL_MS`], with the same inclusive
+    /// timeout as [`CST816S::wait_for_touch_with_interval`]. Returns `Ok(true)` once the finger
+    /// count drops to zero and `Ok(false)` on timeout.
+    ///
+    /// The chip stops answering on the bus when it enters auto-sleep, which it only does after the
+    /// finger has been lifted. A NACK is therefore treated as released, other bus errors are
+    /// returned.
+    pub fn wait_for_release(
+        &mut self,
+        delay: &mut impl DelayNs,
+        timeout_ms: u32,
+    ) -> Result<bool, Error<I2C::Error>> {
+        let released =
+            self.poll_until(
+                delay,
+                timeout_ms,
+                DEFAULT_POLL_INTERVAL_MS,
+                |driver| match driver.device.finger_num().read() {
+                    Ok(fingers) if fingers.value() == 0 => Ok(Some(())),
+                    Ok(_) => Ok(None),
+                    Err(e) if is_nack(&e.0) => Ok(Some(())),
+                    Err(e) => Err(e.into()),
+                },
+            )?;
+        Ok(released.is_some())
+    }
+
+    /// Block until the chip answers with a known `ChipId` or `timeout_ms` elapses.
+    ///
+    /// Call this after [`CST816S::reset`] instead of a fixed delay, slow modules need longer to
+    /// boot than the default [`ResetTimings`](crate::ResetTimings). The chip does not wake from
+    /// deep sleep on its own, reset it first. Polls every [`DEFAULT_POLL_INTERVAL_MS`] with the
+    /// same inclusive timeout as [`CST816S::wait_for_touch_with_interval`].
+    ///
+    /// NACKs and unknown `ChipId` values while booting are retried, other bus errors are
+    /// returned. Returns [`Error::Timeout`] if the chip is not ready in time.
+    pub fn wait_until_ready(
+        &mut self,
+        delay: &mut impl DelayNs,
+        timeout_ms: u32,
+    ) -> Result<(), Error<I2C::Error>> {
+        self.poll_until(
+            delay,
+            timeout_ms,
+            DEFAULT_POLL_INTERVAL_MS,
+            |driver| match driver.probe() {
+                Ok(_) => Ok(Some(())),
+                Err(Error::WrongChipId { .. }) => Ok(None),
+                Err(Error::I2c(e)) if is_nack(&e) => Ok(None),
+                Err(e) => Err(e),
+            },
+        )?
+        .ok_or(Error::Timeout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DEFAULT_ADDRESS, TouchPoint, testing};
+    use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+    use embedded_hal_mock::eh1::{
+        delay::{CheckedDelay, Transaction as DelayTransaction},
+        digital::{self, State},
+        i2c,
+    };
+    use futures_test::test;
+
+    #[test]
+    async fn wait_for_touch_returns_event() {
+        let mut i2c_device = i2c::Mock::new(&testing::report(0x05, 0x10, 0x20));
+        let mut int_pin = digital::Mock::new(&[
+            digital::Transaction::get(State::High),
+            digital::Transaction::get(State::High),
+            digital::Transaction::get(State::Low),
+        ]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut delay = CheckedDelay::new(&[
+            DelayTransaction::blocking_delay_ms(5),
+            DelayTransaction::blocking_delay_ms(5),
+        ]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        let event = touchpad.wait_for_touch(&mut delay, 100).unwrap().unwrap();
+        assert_eq!(event.point, TouchPoint::new(0x10, 0x20));
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+        delay.done();
+    }
+
+    #[test]
+    async fn wait_for_touch_times_out() {
+        let mut i2c_device = i2c::Mock::new(&[]);
+        let mut int_pin = digital::Mock::new(&[
+            digital::Transaction::get(State::High),
+            digital::Transaction::get(State::High),
+            digital::Transaction::get(State::High),
+            digital::Transaction::get(State::High),
+        ]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut delay = CheckedDelay::new(&[
+            DelayTransaction::blocking_delay_ms(5),
+            DelayTransaction::blocking_delay_ms(5),
+            DelayTransaction::blocking_delay_ms(2),
+        ]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        assert!(touchpad.wait_for_touch(&mut delay, 12).unwrap().is_none());
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+        delay.done();
+    }
+
+    fn finger_num(fingers: u8) -> i2c::Transaction {
+        i2c::Transaction::write_read(testing::ADDRESS, vec![0x02], vec![fingers])
+    }
+
+    #[test]
+    async fn wait_for_release_immediate() {
+        let mut i2c_device = i2c::Mock::new(&[finger_num(0)]);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut delay = CheckedDelay::new(&[]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        assert!(touchpad.wait_for_release(&mut delay, 100).unwrap());
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+        delay.done();
+    }
+
+    #[test]
+    async fn wait_for_release_delayed() {
+        let mut i2c_device = i2c::Mock::new(&[
+            finger_num(1),
+            finger_num(1),
+            finger_num(1).with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+        ]);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut delay = CheckedDelay::new(&[
+            DelayTransaction::blocking_delay_ms(5),
+            DelayTransaction::blocking_delay_ms(5),
+        ]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        assert!(touchpad.wait_for_release(&mut delay, 100).unwrap());
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+        delay.done();
+    }
+
+    fn chip_id(value: u8) -> i2c::Transaction {
+        i2c::Transaction::write_read(testing::ADDRESS, vec![0xA7], vec![value])
+    }
+
+    #[test]
+    async fn wait_until_ready_retries() {
+        let mut i2c_device = i2c::Mock::new(&[
+            chip_id(0).with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+            chip_id(0x00),
+            chip_id(0xB4),
+        ]);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut delay = CheckedDelay::new(&[
+            DelayTransaction::blocking_delay_ms(5),
+            DelayTransaction::blocking_delay_ms(5),
+        ]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        touchpad.wait_until_ready(&mut delay, 100).unwrap();
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+        delay.done();
+    }
+
+    #[test]
+    async fn wait_until_ready_times_out() {
+        let mut i2c_device = i2c::Mock::new(&[chip_id(0xFF), chip_id(0xFF)]);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut delay = CheckedDelay::new(&[DelayTransaction::blocking_delay_ms(5)]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        assert_eq!(
+            touchpad.wait_until_ready(&mut delay, 5),
+            Err(Error::Timeout)
+        );
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+        delay.done();
+    }
+
+    #[test]
+    async fn wait_for_release_times_out() {
+        let mut i2c_device = i2c::Mock::new(&[finger_num(1), finger_num(1), finger_num(1)]);
+        let mut int_pin = digital::Mock::new(&[]);
+        let mut rst_pin = digital::Mock::new(&[]);
+        let mut delay = CheckedDelay::new(&[
+            DelayTransaction::blocking_delay_ms(5),
+            DelayTransaction::blocking_delay_ms(3),
+        ]);
+        let mut touchpad =
+            CST816S::new(&mut i2c_device, DEFAULT_ADDRESS, &mut int_pin, &mut rst_pin);
+
+        assert!(!touchpad.wait_for_release(&mut delay, 8).unwrap());
+
+        i2c_device.done();
+        int_pin.done();
+        rst_pin.done();
+        delay.done();
+    }
+}
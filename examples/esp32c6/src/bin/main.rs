@@ -2,7 +2,7 @@
 #![no_main]
 
 use core::fmt::Write;
-use cst816s_device_driver::{device, CST816S};
+use cst816s_device_driver::{device, TouchPoint, CST816S, DEFAULT_ADDRESS};
 use defmt::info;
 use defmt_rtt as _;
 use embedded_graphics::{
@@ -106,11 +106,10 @@ fn main() -> ! {
     .with_sda(i2c_sda)
     .with_scl(i2c_scl);
 
-    let mut touchpad = CST816S::new(i2c, 0x15, touch_interrupt_pin, touch_reset_pin);
+    let mut touchpad = CST816S::new(i2c, DEFAULT_ADDRESS, touch_interrupt_pin, touch_reset_pin);
 
     // Setup Touch Driver
-    touchpad.reset(&mut delay).unwrap();
-    touchpad.init_config().unwrap();
+    touchpad.init(&mut delay).unwrap();
     info!("Driver configured!");
 
     /* End Touch Driver Setup */
@@ -122,13 +121,13 @@ fn main() -> ! {
         .alignment(Alignment::Center)
         .build();
 
-    let mut last_touch = (0, 0);
+    let mut last_touch = TouchPoint::default();
     let mut color = Rgb565::CSS_NAVAJO_WHITE;
 
     loop {
         // Read a touch event from the touch driver and update last_touch if there is a valid event
         if let Some(touch_event) = touchpad.event() {
-            info!("touch Event {}", touch_event.point.0);
+            info!("touch event {}", touch_event);
             color = match touch_event.gesture {
                 device::Gesture::NoGesture => {
                     info!("no gesture");
@@ -141,6 +140,10 @@ fn main() -> ! {
                 device::Gesture::SingleClick => Rgb565::MAGENTA,
                 device::Gesture::DoubleClick => Rgb565::CSS_TAN,
                 device::Gesture::LongPress => Rgb565::CSS_PINK,
+                device::Gesture::Unknown(code) => {
+                    info!("unknown gesture {}", code);
+                    color
+                }
             };
             last_touch = touch_event.point;
         }
@@ -148,7 +151,7 @@ fn main() -> ! {
         // `write` for `heapless::String` returns an error if the buffer is full,
         // but because the buffer here is 9 bytes large, the `(xxx:yyy)` will fit.
         let mut data = String::<9>::new(); // 9 byte string buffer
-        let (x, y) = last_touch;
+        let TouchPoint { x, y } = last_touch;
         let _ = write!(data, "({x:03},{y:03})").unwrap();
 
         // Draw centered text
@@ -56,14 +56,14 @@ impl<A: I2c, B: InputPin, C: OutputPin> App<A, B, C> {
                 device::Gesture::SlideLeft => self.counter -= 1,
                 device::Gesture::SlideRight => self.counter += 1,
                 device::Gesture::SingleClick => {
-                    if touch_event.point.0 <= 120 {
+                    if touch_event.point.x <= 120 {
                         self.counter -= 1;
                     } else {
                         self.counter += 1;
                     }
                 }
                 device::Gesture::DoubleClick => {
-                    if touch_event.point.0 <= 120 {
+                    if touch_event.point.x <= 120 {
                         self.counter -= 10;
                     } else {
                         self.counter += 10;
@@ -3,7 +3,7 @@
 #![no_main]
 
 use cortex_m::delay::Delay;
-use cst816s_device_driver::CST816S;
+use cst816s_device_driver::{CST816S, DEFAULT_ADDRESS};
 use embedded_hal::digital::OutputPin;
 use fugit::RateExtU32;
 use gc9a01a_driver::{Orientation, GC9A01A};
@@ -142,11 +142,10 @@ fn main() -> ! {
         &clocks.system_clock,
     );
 
-    let mut touchpad = CST816S::new(i2c, 0x15, touch_interrupt_pin, touch_reset_pin);
+    let mut touchpad = CST816S::new(i2c, DEFAULT_ADDRESS, touch_interrupt_pin, touch_reset_pin);
 
     // Setup Touch Driver
-    touchpad.reset(&mut delay_wrapper).unwrap();
-    touchpad.init_config().unwrap();
+    touchpad.init(&mut delay_wrapper).unwrap();
 
     // Initialize SPI
     let spi = hal::Spi::<_, _, _, 8>::new(pac.SPI1, (lcd_mosi, lcd_clk));
@@ -7,7 +7,7 @@
 #![no_main]
 
 use cortex_m::delay::Delay;
-use cst816s_device_driver::{CST816S, device};
+use cst816s_device_driver::{CST816S, DEFAULT_ADDRESS, TouchPoint, device};
 use defmt::info;
 use defmt_rtt as _;
 use embedded_graphics::mono_font::ascii::FONT_10X20;
@@ -172,11 +172,10 @@ fn main() -> ! {
         &clocks.system_clock,
     );
 
-    let mut touchpad = CST816S::new(i2c, 0x15, touch_interrupt_pin, touch_reset_pin);
+    let mut touchpad = CST816S::new(i2c, DEFAULT_ADDRESS, touch_interrupt_pin, touch_reset_pin);
 
     // Setup Touch Driver
-    touchpad.reset(&mut delay_wrapper).unwrap();
-    touchpad.init_config().unwrap();
+    touchpad.init(&mut delay_wrapper).unwrap();
 
     /* End Touch Driver Setup */
 
@@ -187,7 +186,7 @@ fn main() -> ! {
         .alignment(Alignment::Center)
         .build();
 
-    let mut last_touch = (0, 0);
+    let mut last_touch = TouchPoint::default();
     let mut color = Rgb565::CSS_NAVAJO_WHITE;
 
     loop {
@@ -202,6 +201,7 @@ fn main() -> ! {
                 device::Gesture::SingleClick => Rgb565::MAGENTA,
                 device::Gesture::DoubleClick => Rgb565::CSS_TAN,
                 device::Gesture::LongPress => Rgb565::CSS_PINK,
+                device::Gesture::Unknown(_) => color,
             };
             last_touch = touch_event.point;
         }
@@ -209,7 +209,7 @@ fn main() -> ! {
         // `write` for `heapless::String` returns an error if the buffer is full,
         // but because the buffer here is 9 bytes large, the `(xxx:yyy)` will fit.
         let mut data = String::<19>::new(); // 9 byte string buffer
-        let (x, y) = last_touch;
+        let TouchPoint { x, y } = last_touch;
         let _ = write!(data, "({x:03},{y:03})").unwrap();
 
         let center = display.bounding_box().center();